@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+use crate::state::PokerTable;
+use crate::error::PokerError;
+
+/// Pending backend accepts the rotation and becomes the table authority
+///
+/// Games already in progress keep the backend they were started with
+/// (PokerGame.backend_account); only new games use the rotated key.
+pub fn handler(ctx: Context<AcceptBackend>) -> Result<()> {
+    let table = &mut ctx.accounts.table;
+    let previous = table.backend;
+
+    table.backend = ctx.accounts.new_backend.key();
+    table.pending_backend = None;
+
+    msg!(
+        "Table {} backend rotated: {} -> {}",
+        table.table_id,
+        previous,
+        table.backend
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AcceptBackend<'info> {
+    #[account(
+        mut,
+        constraint = table.pending_backend == Some(new_backend.key()) @ PokerError::NotPendingBackend,
+        constraint = table.version == PokerTable::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub table: Account<'info, PokerTable>,
+
+    pub new_backend: Signer<'info>,
+}
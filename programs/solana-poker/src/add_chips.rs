@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::state::{PokerTable, PlayerSeat, ProtocolConfig};
+use crate::error::PokerError;
+
+/// Seated player tops up (or rebuys) their stack between hands
+///
+/// The resulting stack must stay within the table's buy-in range, so a
+/// busted player rebuys at least buy_in_min and nobody exceeds buy_in_max.
+pub fn handler(ctx: Context<AddChips>, amount: u64) -> Result<()> {
+    let table = &ctx.accounts.table;
+    let player_seat = &mut ctx.accounts.player_seat;
+
+    require!(amount > 0, PokerError::InvalidBuyIn);
+
+    // Check no game in progress
+    require!(table.current_game.is_none(), PokerError::GameInProgress);
+
+    let new_stack = player_seat
+        .chips
+        .checked_add(amount)
+        .ok_or(PokerError::InvalidBuyIn)?;
+    require!(
+        new_stack >= table.buy_in_min && new_stack <= table.buy_in_max,
+        PokerError::InvalidBuyIn
+    );
+
+    // Transfer SOL from player to vault
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.player.to_account_info(),
+                to: ctx.accounts.vault.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    player_seat.chips = new_stack;
+
+    msg!(
+        "Player {} at seat {} added {} lamports. Stack: {}",
+        ctx.accounts.player.key(),
+        player_seat.seat_index,
+        amount,
+        player_seat.chips
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AddChips<'info> {
+    #[account(
+        constraint = table.version == PokerTable::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub table: Account<'info, PokerTable>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ PokerError::ProtocolPaused
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// CHECK: Vault PDA to receive SOL
+    #[account(
+        mut,
+        seeds = [b"vault", table.key().as_ref()],
+        bump
+    )]
+    pub vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"player_seat", table.key().as_ref(), player.key().as_ref()],
+        bump = player_seat.bump,
+        constraint = player_seat.version == PlayerSeat::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub player_seat: Account<'info, PlayerSeat>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
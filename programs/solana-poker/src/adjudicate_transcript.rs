@@ -0,0 +1,96 @@
+use crate::constants::{DISPUTE_WINDOW_SLOTS, MAX_PLAYERS};
+use crate::error::PokerError;
+use crate::state::{GameStage, OverruledResult, PokerGame, Settlement, Transcript};
+use anchor_lang::prelude::*;
+
+/// Correct a proposed settlement that contradicts the replayed transcript
+///
+/// Permissionless while the dispute window is open. The transcript must
+/// have reached the end of the betting. When the backend's bets or folds
+/// differ from the replay, the settlement is rewritten with the legal
+/// final state:
+/// - one player left: they take the whole pot
+/// - a showdown: every seat gets its own bets back (the hand is voided)
+///
+/// A transcript holding a proven equivocation (see prove_equivocation) is
+/// no evidence: the seat that signed two actions for one turn could pick
+/// whichever suits it. The overruled result is kept and the dispute window
+/// reopens so such a proof can still put it back.
+///
+/// finalize_settlement then applies the corrected result as usual.
+pub fn handler(ctx: Context<AdjudicateTranscript>) -> Result<()> {
+    let game = &ctx.accounts.game;
+    let settlement = &mut ctx.accounts.settlement;
+    let transcript = &ctx.accounts.transcript;
+
+    let clock = Clock::get()?;
+    require!(
+        clock.slot <= settlement.deadline_slot,
+        PokerError::DisputeWindowClosed
+    );
+    require!(transcript.complete, PokerError::TranscriptIncomplete);
+    require!(transcript.equivocated_mask == 0, PokerError::TranscriptEquivocated);
+    require!(
+        transcript.dealt_mask == settlement.dealt_mask,
+        PokerError::InvalidSeatAccounts
+    );
+
+    let matches = transcript.folded_mask == settlement.folded_mask
+        && transcript.committed == settlement.total_bets;
+    require!(!matches, PokerError::TranscriptMatchesSettlement);
+
+    let pot: u64 = transcript.committed.iter().sum();
+    let live = transcript.live_mask();
+
+    settlement.overruled = Some(OverruledResult {
+        total_bets: settlement.total_bets,
+        payouts: settlement.payouts,
+        second_payouts: settlement.second_payouts,
+        folded_mask: settlement.folded_mask,
+    });
+    settlement.deadline_slot = clock.slot + DISPUTE_WINDOW_SLOTS;
+
+    settlement.total_bets = transcript.committed;
+    settlement.folded_mask = transcript.folded_mask;
+    settlement.second_payouts = [0; MAX_PLAYERS as usize];
+    if live.count_ones() == 1 {
+        let winner = live.trailing_zeros() as usize;
+        settlement.payouts = [0; MAX_PLAYERS as usize];
+        settlement.payouts[winner] = pot;
+        settlement.voided = false;
+        // A lone winner takes both boards' halves
+        if game.run_twice {
+            settlement.second_payouts[winner] =
+                Settlement::second_board_share(&transcript.committed, live);
+        }
+        msg!("Game {} adjudicated: seat {} wins {}", game.game_id, winner, pot);
+    } else {
+        settlement.payouts = transcript.committed;
+        settlement.voided = true;
+        msg!("Game {} adjudicated: showdown voided, bets returned", game.game_id);
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AdjudicateTranscript<'info> {
+    #[account(
+        constraint = game.stage == GameStage::PendingSettlement @ PokerError::InvalidGameStage,
+        constraint = game.version == PokerGame::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub game: Account<'info, PokerGame>,
+
+    #[account(
+        mut,
+        seeds = [b"settlement", game.key().as_ref()],
+        bump = settlement.bump
+    )]
+    pub settlement: Account<'info, Settlement>,
+
+    #[account(
+        seeds = [b"transcript", game.key().as_ref()],
+        bump = transcript.bump
+    )]
+    pub transcript: Account<'info, Transcript>,
+}
@@ -0,0 +1,103 @@
+use crate::constants::{COMMUNITY_CARDS, MAX_PLAYERS};
+use crate::ed25519::signers_of;
+use crate::error::PokerError;
+use crate::state::{GameStage, PokerGame, PokerTable, Transcript};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use anchor_lang::solana_program::sysvar;
+
+/// Domain tag prefixed to every signed run-twice agreement
+pub const RUN_TWICE_DOMAIN: &[u8] = b"solana-poker:run-twice";
+
+/// Digest all-in players sign to run the board twice
+///
+/// sha256(RUN_TWICE_DOMAIN || game || game_id (le) || revealed)
+pub fn agreement_digest(game: &Pubkey, game_id: u64, revealed: u8) -> [u8; 32] {
+    hashv(&[
+        RUN_TWICE_DOMAIN,
+        game.as_ref(),
+        &game_id.to_le_bytes(),
+        &[revealed],
+    ])
+    .to_bytes()
+}
+
+/// Record an all-in players' agreement to run the board twice
+///
+/// Only once the replayed transcript shows betting over with every live
+/// seat all-in (one may still have chips behind). `revealed` is the number
+/// of community cards shown at that point (0, 3 or 4); the second board
+/// keeps those and redraws the rest. Every live seat's player must have
+/// signed agreement_digest through an Ed25519 precompile instruction
+/// earlier in the transaction.
+///
+/// The game goes back to dealing: process_cards_batch and
+/// confirm_cards_batch draw the second board, avoiding every card already
+/// dealt, before play resumes. Each pot is then settled half on each
+/// board, the odd chip going to the first (Settlement::run_twice_split).
+pub fn handler(ctx: Context<AgreeRunItTwice>, revealed: u8) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let transcript = &ctx.accounts.transcript;
+
+    require!(
+        !game.run_twice && transcript.all_in_board() == Some(revealed),
+        PokerError::InvalidRunTwice
+    );
+
+    // Every seat still contesting the pot has to agree
+    let digest = agreement_digest(&game.key(), game.game_id, revealed);
+    let signers = signers_of(&ctx.accounts.instructions, &digest)?;
+    let live = transcript.live_mask();
+    for seat in (0..MAX_PLAYERS).filter(|&seat| live & (1 << seat) != 0) {
+        require!(
+            signers.contains(&transcript.players[seat as usize]),
+            PokerError::MissingPlayerSignature
+        );
+    }
+
+    // The second board shares the cards already revealed
+    let shared = revealed as usize;
+    let board = game.community_cards;
+    game.second_board[..shared].copy_from_slice(&board[..shared]);
+    game.second_board_from = revealed;
+    game.run_twice = true;
+    game.cards_processed = false;
+
+    msg!(
+        "Game {} runs it twice: {} board cards redrawn, batches {}..{}",
+        game.game_id,
+        COMMUNITY_CARDS - revealed,
+        game.first_board_batches(),
+        game.batch_count()
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AgreeRunItTwice<'info> {
+    #[account(
+        constraint = table.version == PokerTable::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub table: Account<'info, PokerTable>,
+
+    #[account(
+        mut,
+        constraint = game.table == table.key() @ PokerError::NoActiveGame,
+        constraint = game.stage == GameStage::Playing @ PokerError::InvalidGameStage,
+        constraint = game.cards_processed @ PokerError::CardsNotProcessed,
+        constraint = game.version == PokerGame::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub game: Account<'info, PokerGame>,
+
+    #[account(
+        seeds = [b"transcript", game.key().as_ref()],
+        bump = transcript.bump,
+        constraint = transcript.version == Transcript::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub transcript: Account<'info, Transcript>,
+
+    /// CHECK: Instructions sysvar holding the players' Ed25519 signatures
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+}
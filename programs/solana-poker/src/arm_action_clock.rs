@@ -0,0 +1,43 @@
+use crate::constants::{ACTION_TIMEOUT_SLOTS, EMPTY_SEAT};
+use crate::error::PokerError;
+use crate::state::{GameStage, PokerGame};
+use anchor_lang::prelude::*;
+
+/// Backend starts the on-chain action clock for every seat of a hand
+///
+/// Used once play has moved to the transcript, e.g. when a player stops
+/// responding off-chain. The seat to act, if any, gets a full
+/// ACTION_TIMEOUT_SLOTS from now; timeout_action may then act for any
+/// seat of the hand that lets its turn run out.
+pub fn handler(ctx: Context<ArmActionClock>) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+
+    game.clock_armed = true;
+    if game.to_act != EMPTY_SEAT {
+        game.action_deadline = Clock::get()?.slot + ACTION_TIMEOUT_SLOTS;
+    }
+
+    msg!(
+        "Game {} action clock started, seat {} to act by slot {}",
+        game.game_id,
+        game.to_act,
+        game.action_deadline
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ArmActionClock<'info> {
+    #[account(
+        mut,
+        constraint = game.stage == GameStage::Playing @ PokerError::InvalidGameStage,
+        constraint = game.version == PokerGame::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub game: Account<'info, PokerGame>,
+
+    #[account(
+        constraint = backend.key() == game.backend_account @ PokerError::NotBackend
+    )]
+    pub backend: Signer<'info>,
+}
@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+use inco_lightning::cpi::accounts::VerifySignature;
+use inco_lightning::cpi::is_validsignature;
+use inco_lightning::types::Euint128;
+
+/// Verify Inco attested decryptions of `handles`
+///
+/// The transaction must carry the Ed25519 instructions returned by the
+/// attested decrypt; handles and plaintexts are passed as 16-byte
+/// little-endian buffers, matching handleToBuffer / plaintextToBuffer.
+pub fn verify_decryptions<'info>(
+    inco_lightning_program: AccountInfo<'info>,
+    instructions: AccountInfo<'info>,
+    signer: AccountInfo<'info>,
+    handles: &[Euint128],
+    plaintexts: &[u128],
+) -> Result<()> {
+    let cpi_ctx = CpiContext::new(
+        inco_lightning_program,
+        VerifySignature {
+            instructions,
+            signer,
+        },
+    );
+
+    is_validsignature(
+        cpi_ctx,
+        handles.len() as u8,
+        Some(handles.iter().map(|h| h.0.to_le_bytes().to_vec()).collect()),
+        Some(plaintexts.iter().map(|p| p.to_le_bytes().to_vec()).collect()),
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,270 @@
+use crate::attestation::verify_decryptions;
+use crate::constants::DISPUTE_WINDOW_SLOTS;
+use crate::error::PokerError;
+use crate::hand_eval::{
+    card_index, evaluate, evaluate_omaha, evaluate_short_deck, short_deck_card_index,
+};
+use crate::state::{GameStage, GameVariant, PlayerSeat, PokerGame, PokerTable, Settlement};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use inco_lightning::program::IncoLightning;
+
+/// Seated player disputes a proposed settlement with card evidence
+///
+/// The challenger submits Inco attested decryptions of the board, their own
+/// hole cards and the hole cards of a seat that was paid (the accused).
+/// The transaction must include the Ed25519 instructions from the attested
+/// decrypt. Pass every dealt-in seat via remaining_accounts. Decrypt
+/// access to the accused's cards and the board comes from
+/// open_challenge_evidence.
+///
+/// The result is corrected when both seats reached showdown, the challenger
+/// put in at least as much as the accused (so is eligible for every pot the
+/// accused is) and holds the strictly better hand, yet the accused was paid:
+/// what the accused won on that board goes to the challenger, and the
+/// dispute window reopens so any other seat that beats the challenger can
+/// take it in turn. No chips move until finalize_settlement.
+///
+/// Omaha hands are scored from exactly two hole cards and three board
+/// cards; short-deck hands use the 36-card deck and its hand ranking.
+///
+/// When the board ran twice, second_board carries the second board's
+/// decryptions and the challenge succeeds on either board that paid the
+/// accused while the challenger held the better hand there, correcting
+/// that board only.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ChallengeSettlement<'info>>,
+    accused_seat: u8,
+    board: [u128; 5],
+    challenger_cards: Vec<u128>,
+    accused_cards: Vec<u128>,
+    second_board: Option<[u128; 5]>,
+) -> Result<()> {
+    let table = &ctx.accounts.table;
+    let game = &ctx.accounts.game;
+    let settlement = &mut ctx.accounts.settlement;
+    let player = &ctx.accounts.player;
+
+    let clock = Clock::get()?;
+    require!(
+        clock.slot <= settlement.deadline_slot,
+        PokerError::DisputeWindowClosed
+    );
+    require!(
+        ctx.remaining_accounts.len() == game.player_count as usize,
+        PokerError::InvalidSeatAccounts
+    );
+
+    let table_key = table.key();
+    let mut seen_mask: u16 = 0;
+    let mut challenger_seat: Option<u8> = None;
+
+    for seat_info in ctx.remaining_accounts.iter() {
+        let seat: Account<PlayerSeat> = Account::try_from(seat_info)?;
+        require!(seat.version == PlayerSeat::VERSION, PokerError::UnsupportedAccountVersion);
+
+        let expected = Pubkey::create_program_address(
+            &[
+                b"player_seat",
+                table_key.as_ref(),
+                seat.player.as_ref(),
+                &[seat.bump],
+            ],
+            ctx.program_id,
+        )
+        .map_err(|_| PokerError::PlayerNotAtTable)?;
+        require!(expected == *seat_info.key, PokerError::PlayerNotAtTable);
+        require!(seat.game == game.key(), PokerError::SeatNotInGame);
+
+        let seat_bit = 1u16 << seat.seat_index;
+        require!(seen_mask & seat_bit == 0, PokerError::InvalidSeatAccounts);
+        seen_mask |= seat_bit;
+
+        if seat.player == player.key() {
+            challenger_seat = Some(seat.seat_index);
+        }
+    }
+    require!(seen_mask == settlement.dealt_mask, PokerError::InvalidSeatAccounts);
+
+    // ===== ELIGIBILITY =====
+    let challenger_seat = challenger_seat.ok_or(PokerError::PlayerNotAtTable)?;
+    let (challenger, accused) = (challenger_seat as usize, accused_seat as usize);
+    require!(
+        !settlement.voided
+            && accused_seat != challenger_seat
+            && accused < settlement.payouts.len()
+            && settlement.is_live(challenger_seat)
+            && settlement.is_live(accused_seat)
+            && settlement.payouts[accused] > 0
+            && settlement.total_bets[challenger] >= settlement.total_bets[accused],
+        PokerError::InvalidChallenge
+    );
+
+    // ===== EVIDENCE =====
+    let evidence = Evidence {
+        board,
+        challenger_cards: &challenger_cards,
+        accused_cards: &accused_cards,
+        second_board,
+    };
+    let scores = score_evidence(
+        game,
+        &evidence,
+        challenger_seat,
+        accused_seat,
+        ctx.accounts.inco_lightning_program.to_account_info(),
+        ctx.accounts.instructions.to_account_info(),
+        player.to_account_info(),
+    )?;
+
+    // Any board that paid the accused despite the challenger's better hand
+    let (first_paid, second_paid) = settlement.board_payouts(accused_seat);
+    let (board_index, &(challenger_score, accused_score)) = scores
+        .iter()
+        .zip([first_paid, second_paid])
+        .enumerate()
+        .find(|&(_, (&(challenger_wins, accused_wins), paid))| {
+            paid > 0 && challenger_wins > accused_wins
+        })
+        .map(|(board_index, (score, _))| (board_index, score))
+        .ok_or(PokerError::ChallengeRejected)?;
+
+    // ===== CORRECT THE RESULT =====
+    let moved = settlement.move_board_payout(board_index, accused_seat, challenger_seat);
+    settlement.deadline_slot = clock.slot + DISPUTE_WINDOW_SLOTS;
+
+    msg!(
+        "Game {} settlement corrected: seat {} beats paid seat {} on board {} ({} > {}), {} moved",
+        game.game_id,
+        challenger_seat,
+        accused_seat,
+        board_index + 1,
+        challenger_score,
+        accused_score,
+        moved
+    );
+
+    Ok(())
+}
+
+/// Card evidence submitted with a challenge, as attested plaintexts
+pub struct Evidence<'a> {
+    pub board: [u128; 5],
+    pub challenger_cards: &'a [u128],
+    pub accused_cards: &'a [u128],
+    pub second_board: Option<[u128; 5]>,
+}
+
+/// Verify a challenge's attested decryptions and score both hands
+///
+/// The plaintexts are checked against the board and hole card handles the
+/// game recorded. Returns the (challenger, accused) scores on the board,
+/// then on the second board when it ran twice.
+pub fn score_evidence<'info>(
+    game: &PokerGame,
+    evidence: &Evidence,
+    challenger_seat: u8,
+    accused_seat: u8,
+    inco_lightning_program: AccountInfo<'info>,
+    instructions: AccountInfo<'info>,
+    player: AccountInfo<'info>,
+) -> Result<Vec<(u32, u32)>> {
+    let &Evidence {
+        board,
+        challenger_cards,
+        accused_cards,
+        second_board,
+    } = evidence;
+
+    let per_seat = game.hole_cards_per_player();
+    require!(
+        challenger_cards.len() == per_seat && accused_cards.len() == per_seat,
+        PokerError::InvalidChallenge
+    );
+    let challenger_hole = game
+        .get_player_hole_cards(challenger_seat)
+        .ok_or(PokerError::SeatNotInGame)?;
+    let accused_hole = game
+        .get_player_hole_cards(accused_seat)
+        .ok_or(PokerError::SeatNotInGame)?;
+
+    let mut handles = game.community_cards.to_vec();
+    handles.extend_from_slice(challenger_hole);
+    handles.extend_from_slice(accused_hole);
+    let mut plaintexts = board.to_vec();
+    plaintexts.extend_from_slice(challenger_cards);
+    plaintexts.extend_from_slice(accused_cards);
+
+    // The second board shares the cards revealed before the agreement
+    let mut boards = vec![board];
+    match (game.run_twice, second_board) {
+        (false, None) => {}
+        (true, Some(second)) => {
+            let from = game.second_board_from as usize;
+            require!(second[..from] == board[..from], PokerError::InvalidChallenge);
+            handles.extend_from_slice(&game.second_board[from..]);
+            plaintexts.extend_from_slice(&second[from..]);
+            boards.push(second);
+        }
+        _ => return err!(PokerError::InvalidChallenge),
+    }
+
+    verify_decryptions(
+        inco_lightning_program,
+        instructions,
+        player,
+        &handles,
+        &plaintexts,
+    )?;
+
+    let variant = game.variant;
+    let index_of = match variant {
+        GameVariant::ShortDeck => short_deck_card_index,
+        GameVariant::Holdem | GameVariant::Omaha => card_index,
+    };
+    let hand_of = |board: &[u128; 5], hole: &[u128]| {
+        let board: Vec<u8> = board.iter().map(|&p| index_of(p)).collect();
+        let hole: Vec<u8> = hole.iter().map(|&p| index_of(p)).collect();
+        match variant {
+            GameVariant::Holdem => evaluate(&[board, hole].concat()),
+            GameVariant::Omaha => evaluate_omaha(&hole, &board),
+            GameVariant::ShortDeck => evaluate_short_deck(&[board, hole].concat()),
+        }
+    };
+
+    Ok(boards
+        .iter()
+        .map(|board| (hand_of(board, challenger_cards), hand_of(board, accused_cards)))
+        .collect())
+}
+
+#[derive(Accounts)]
+pub struct ChallengeSettlement<'info> {
+    #[account(
+        constraint = table.version == PokerTable::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub table: Account<'info, PokerTable>,
+
+    #[account(
+        constraint = game.table == table.key() @ PokerError::NoActiveGame,
+        constraint = game.stage == GameStage::PendingSettlement @ PokerError::InvalidGameStage,
+        constraint = game.version == PokerGame::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub game: Account<'info, PokerGame>,
+
+    #[account(
+        mut,
+        seeds = [b"settlement", game.key().as_ref()],
+        bump = settlement.bump
+    )]
+    pub settlement: Account<'info, Settlement>,
+
+    /// Challenging player, must hold a seat dealt into the game
+    pub player: Signer<'info>,
+
+    /// CHECK: Instructions sysvar holding the attestation Ed25519 instructions
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+
+    pub inco_lightning_program: Program<'info, IncoLightning>,
+}
@@ -0,0 +1,212 @@
+use crate::challenge_settlement::{score_evidence, Evidence};
+use crate::constants::{DISPUTE_WINDOW_SLOTS, INCO_AUTHORITY_SEED};
+use crate::error::PokerError;
+use crate::inco_authority::top_up;
+use crate::state::{ConfidentialSettlement, GameStage, PlayerSeat, PokerGame, PokerTable};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use inco_lightning::cpi::accounts::Operation;
+use inco_lightning::cpi::{self, e_add, e_ge, e_select, e_sub};
+use inco_lightning::program::IncoLightning;
+use inco_lightning::types::Euint128;
+
+/// Seated player disputes a confidential table's proposed result
+///
+/// Same card evidence as challenge_settlement: attested decryptions of the
+/// board, the challenger's hole cards and the accused's, with the Ed25519
+/// instructions from the attested decrypt in the transaction. Pass every
+/// dealt-in seat via remaining_accounts.
+///
+/// Both seats must have reached showdown and the challenger must hold the
+/// strictly better hand. Whether the accused was paid and whether the
+/// challenger put in at least as much (so is eligible for every pot the
+/// accused is) stays encrypted: when both hold, the accused's payout moves
+/// to the challenger, otherwise the result stays as it was. Either way the
+/// dispute window reopens so any seat that beats the challenger can take
+/// it in turn; each seat can be challenged once by each other seat. When
+/// the board ran twice, what each board paid stays encrypted, so the
+/// challenger must hold the better hand on both boards. No stack changes
+/// until finalize_settlement_confidential.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ChallengeSettlementConfidential<'info>>,
+    accused_seat: u8,
+    board: [u128; 5],
+    challenger_cards: Vec<u128>,
+    accused_cards: Vec<u128>,
+    second_board: Option<[u128; 5]>,
+) -> Result<()> {
+    let table = &ctx.accounts.table;
+    let game = &ctx.accounts.game;
+    let settlement = &mut ctx.accounts.settlement;
+    let player = &ctx.accounts.player;
+
+    let clock = Clock::get()?;
+    require!(
+        clock.slot <= settlement.deadline_slot,
+        PokerError::DisputeWindowClosed
+    );
+    require!(
+        ctx.remaining_accounts.len() == game.player_count as usize,
+        PokerError::InvalidSeatAccounts
+    );
+
+    let table_key = table.key();
+    let mut seen_mask: u16 = 0;
+    let mut challenger_seat: Option<u8> = None;
+
+    for seat_info in ctx.remaining_accounts.iter() {
+        let seat: Account<PlayerSeat> = Account::try_from(seat_info)?;
+        require!(seat.version == PlayerSeat::VERSION, PokerError::UnsupportedAccountVersion);
+
+        let expected = Pubkey::create_program_address(
+            &[
+                b"player_seat",
+                table_key.as_ref(),
+                seat.player.as_ref(),
+                &[seat.bump],
+            ],
+            ctx.program_id,
+        )
+        .map_err(|_| PokerError::PlayerNotAtTable)?;
+        require!(expected == *seat_info.key, PokerError::PlayerNotAtTable);
+        require!(seat.game == game.key(), PokerError::SeatNotInGame);
+
+        let seat_bit = 1u16 << seat.seat_index;
+        require!(seen_mask & seat_bit == 0, PokerError::InvalidSeatAccounts);
+        seen_mask |= seat_bit;
+
+        if seat.player == player.key() {
+            challenger_seat = Some(seat.seat_index);
+        }
+    }
+    require!(seen_mask == settlement.dealt_mask, PokerError::InvalidSeatAccounts);
+
+    // ===== ELIGIBILITY =====
+    let challenger_seat = challenger_seat.ok_or(PokerError::PlayerNotAtTable)?;
+    require!(
+        accused_seat != challenger_seat
+            && (accused_seat as usize) < settlement.payouts.len()
+            && settlement.is_live(challenger_seat)
+            && settlement.is_live(accused_seat)
+            && settlement.challenged_by[accused_seat as usize] & (1 << challenger_seat) == 0,
+        PokerError::InvalidChallenge
+    );
+
+    // ===== EVIDENCE =====
+    let evidence = Evidence {
+        board,
+        challenger_cards: &challenger_cards,
+        accused_cards: &accused_cards,
+        second_board,
+    };
+    let scores = score_evidence(
+        game,
+        &evidence,
+        challenger_seat,
+        accused_seat,
+        ctx.accounts.inco_lightning_program.to_account_info(),
+        ctx.accounts.instructions.to_account_info(),
+        player.to_account_info(),
+    )?;
+
+    let mut challenger_score = 0;
+    let mut accused_score = 0;
+    for &(challenger_wins, accused_wins) in scores.iter() {
+        challenger_score = challenger_wins;
+        accused_score = accused_wins;
+        require!(
+            challenger_score > accused_score,
+            PokerError::ChallengeRejected
+        );
+    }
+
+    // ===== CORRECT THE RESULT =====
+    let cpi_program = ctx.accounts.inco_lightning_program.to_account_info();
+    let authority = ctx.accounts.inco_authority.to_account_info();
+    let payer = player.to_account_info();
+    let system_program = ctx.accounts.system_program.to_account_info();
+    let seeds = &[INCO_AUTHORITY_SEED, &[ctx.bumps.inco_authority]];
+    let signer = &[&seeds[..]];
+
+    let op_accounts = Operation {
+        signer: authority.clone(),
+    };
+    let op = || CpiContext::new_with_signer(cpi_program.clone(), op_accounts.clone(), signer);
+    top_up(&authority, &payer, &system_program)?;
+
+    // The accused's payout moves when the challenger covered the accused's
+    // bets, so was eligible for every pot the accused won; an unpaid
+    // accused moves nothing
+    let (challenger, accused) = (challenger_seat as usize, accused_seat as usize);
+    let zero: Euint128 = cpi::as_euint128(op(), 0)?;
+    let covered = e_ge(
+        op(),
+        settlement.total_bets[challenger],
+        settlement.total_bets[accused],
+        0,
+    )?;
+    let moved = e_select(op(), covered, settlement.payouts[accused], zero, 0)?;
+    settlement.payouts[challenger] = e_add(op(), settlement.payouts[challenger], moved, 0)?;
+    settlement.payouts[accused] = e_sub(op(), settlement.payouts[accused], moved, 0)?;
+
+    top_up(&authority, &payer, &system_program)?;
+
+    settlement.challenged_by[accused] |= 1 << challenger_seat;
+    settlement.deadline_slot = clock.slot + DISPUTE_WINDOW_SLOTS;
+
+    msg!(
+        "Game {} confidential settlement challenged: seat {} beats seat {} ({} > {})",
+        game.game_id,
+        challenger_seat,
+        accused_seat,
+        challenger_score,
+        accused_score
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ChallengeSettlementConfidential<'info> {
+    #[account(
+        constraint = table.confidential @ PokerError::NotConfidentialTable,
+        constraint = table.version == PokerTable::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub table: Account<'info, PokerTable>,
+
+    #[account(
+        constraint = game.table == table.key() @ PokerError::NoActiveGame,
+        constraint = game.stage == GameStage::PendingSettlement @ PokerError::InvalidGameStage,
+        constraint = game.version == PokerGame::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub game: Account<'info, PokerGame>,
+
+    #[account(
+        mut,
+        seeds = [b"confidential_settlement", game.key().as_ref()],
+        bump = settlement.bump,
+        constraint = settlement.version == ConfidentialSettlement::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub settlement: Account<'info, ConfidentialSettlement>,
+
+    /// Challenging player, must hold a seat dealt into the game; funds the
+    /// Inco operations
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    /// CHECK: Program PDA that owns the stack handles
+    #[account(
+        mut,
+        seeds = [INCO_AUTHORITY_SEED],
+        bump
+    )]
+    pub inco_authority: AccountInfo<'info>,
+
+    /// CHECK: Instructions sysvar holding the attestation Ed25519 instructions
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+
+    pub inco_lightning_program: Program<'info, IncoLightning>,
+
+    pub system_program: Program<'info, System>,
+}
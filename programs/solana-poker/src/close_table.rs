@@ -0,0 +1,91 @@
+use anchor_lang::prelude::*;
+use crate::state::{LobbyPage, PokerTable, TableAccess};
+use crate::error::PokerError;
+
+/// Creator closes an empty table and reclaims its rent
+///
+/// Flow:
+/// 1. Validate no players are seated and no game is running
+/// 2. Drain every lamport left in the vault PDA to the recipient
+/// 3. Close the table account (and its access lists, if created), sending
+///    their rent to the recipient
+pub fn handler(ctx: Context<CloseTable>) -> Result<()> {
+    let table = &ctx.accounts.table;
+
+    require!(table.player_count == 0, PokerError::TableNotEmpty);
+    require!(table.current_game.is_none(), PokerError::GameInProgress);
+
+    let table_key = table.key();
+    ctx.accounts.lobby_page.remove(table_key)?;
+
+    let seeds = &[b"vault", table_key.as_ref(), &[ctx.bumps.vault]];
+    let signer = &[&seeds[..]];
+
+    // Emptying a system-owned account closes it
+    let vault_balance = ctx.accounts.vault.lamports();
+    if vault_balance > 0 {
+        let cpi_accounts = anchor_lang::system_program::Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.recipient.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.system_program.to_account_info();
+
+        anchor_lang::system_program::transfer(
+            CpiContext::new_with_signer(cpi_program, cpi_accounts, signer),
+            vault_balance,
+        )?;
+    }
+
+    msg!(
+        "Table {} closed. Vault drained {} lamports to {}",
+        table.table_id,
+        vault_balance,
+        ctx.accounts.recipient.key()
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseTable<'info> {
+    #[account(
+        mut,
+        close = recipient,
+        constraint = table.creator == creator.key() @ PokerError::NotCreator,
+        constraint = table.version == PokerTable::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub table: Account<'info, PokerTable>,
+
+    /// CHECK: Vault PDA to drain
+    #[account(
+        mut,
+        seeds = [b"vault", table.key().as_ref()],
+        bump
+    )]
+    pub vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"lobby", table.lobby_page.to_le_bytes().as_ref()],
+        bump = lobby_page.bump
+    )]
+    pub lobby_page: Account<'info, LobbyPage>,
+
+    /// Allowlist and bans of the table; absent for tables that never
+    /// created them
+    #[account(
+        mut,
+        close = recipient,
+        seeds = [b"table_access", table.key().as_ref()],
+        bump = table_access.bump
+    )]
+    pub table_access: Option<Account<'info, TableAccess>>,
+
+    pub creator: Signer<'info>,
+
+    /// CHECK: Receives the vault balance and table rent
+    #[account(mut)]
+    pub recipient: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,36 @@
+use crate::error::PokerError;
+use crate::state::Transcript;
+use anchor_lang::prelude::*;
+
+/// Close a transcript once its game account is gone, refunding the payer
+pub fn handler(ctx: Context<CloseTranscript>) -> Result<()> {
+    require!(
+        ctx.accounts.game.data_is_empty(),
+        PokerError::GameStillOpen
+    );
+
+    msg!("Transcript for game {} closed", ctx.accounts.game.key());
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseTranscript<'info> {
+    /// CHECK: Game the transcript replayed, must already be closed
+    #[account(address = transcript.game)]
+    pub game: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        close = payer,
+        seeds = [b"transcript", game.key().as_ref()],
+        bump = transcript.bump
+    )]
+    pub transcript: Account<'info, Transcript>,
+
+    /// CHECK: Original payer of the transcript rent
+    #[account(
+        mut,
+        address = transcript.payer
+    )]
+    pub payer: AccountInfo<'info>,
+}
@@ -0,0 +1,87 @@
+use crate::attestation::verify_decryptions;
+use crate::error::PokerError;
+use crate::state::{GameStage, PokerGame, PokerTable};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use inco_lightning::program::IncoLightning;
+
+/// Backend confirms the last drawn card batch
+///
+/// The backend submits the attested decryption of the batch's
+/// duplicate_flag (the transaction must carry the Ed25519 instructions
+/// from the attested decrypt):
+/// - 0: the batch is accepted; after the last batch the game moves to Playing
+/// - otherwise bit i marks the batch's i-th card as a repeat of an earlier
+///   one; only those cards are drawn again, the rest of the batch is kept
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, ConfirmCardsBatch<'info>>,
+    duplicate_flag: u128,
+) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+
+    require!(game.batch_pending, PokerError::NoBatchPending);
+
+    verify_decryptions(
+        ctx.accounts.inco_lightning_program.to_account_info(),
+        ctx.accounts.instructions.to_account_info(),
+        ctx.accounts.backend.to_account_info(),
+        &[game.duplicate_flag],
+        &[duplicate_flag],
+    )?;
+
+    game.batch_pending = false;
+
+    if duplicate_flag != 0 {
+        game.redraw_mask = duplicate_flag as u8;
+        msg!(
+            "Batch {} repeated a card, redraw mask {:#04b}",
+            game.next_batch,
+            game.redraw_mask
+        );
+        return Ok(());
+    }
+
+    game.redraw_mask = 0;
+
+    game.used_cards = game.batch_used_cards;
+    game.next_batch += 1;
+
+    // ===== FINALIZE AFTER LAST BATCH =====
+    if game.next_batch == game.batch_count() {
+        game.cards_processed = true;
+        game.stage = GameStage::Playing;
+        msg!("All cards processed! cards_processed=true, stage=Playing");
+        msg!("Backend can now proceed with off-chain gameplay");
+    } else {
+        msg!("Batch {} done", game.next_batch - 1);
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ConfirmCardsBatch<'info> {
+    #[account(
+        constraint = table.version == PokerTable::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub table: Account<'info, PokerTable>,
+
+    #[account(
+        mut,
+        constraint = game.table == table.key() @ PokerError::NoActiveGame,
+        constraint = game.is_dealing() @ PokerError::InvalidGameStage,
+        constraint = game.version == PokerGame::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub game: Account<'info, PokerGame>,
+
+    #[account(
+        constraint = backend.key() == game.backend_account @ PokerError::NotBackend
+    )]
+    pub backend: Signer<'info>,
+
+    /// CHECK: Instructions sysvar holding the attestation Ed25519 instructions
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+
+    pub inco_lightning_program: Program<'info, IncoLightning>,
+}
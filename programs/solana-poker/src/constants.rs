@@ -3,17 +3,61 @@ use anchor_lang::prelude::*;
 /// Inco Lightning program ID on devnet
 pub const INCO_LIGHTNING_ID: Pubkey = pubkey!("5sjEbPiqgZrYwR31ahR6Uk9wf5awoX61YGg7jExQSwaj");
 
+/// Seed of the program PDA that owns every encrypted card handle
+pub const INCO_AUTHORITY_SEED: &[u8] = b"inco_authority";
 
-pub const MAX_PLAYERS: u8 = 5;
+/// Public spectator key allowed on community cards once their street is
+/// reached (see reveal_street)
+///
+/// Its secret is published on purpose: the keypair is
+/// Keypair::from_seed(sha256(b"solana-poker:spectator")), so anyone can sign
+/// Inco decrypt requests as this key.
+pub const SPECTATOR_VIEWER: Pubkey = pubkey!("D3GuUusgH9w67ARVSDoPfJzskRKiiyPCt3niaEaouSgA");
+
+/// Lamports kept on the Inco authority PDA to pay for allowance accounts
+pub const INCO_AUTHORITY_FLOAT: u64 = 10_000_000;
+
+
+pub const MAX_PLAYERS: u8 = 10;
 pub const MIN_PLAYERS: u8 = 2;
 
+/// Marks an unused slot in PokerGame.shuffled_indices
+pub const EMPTY_SEAT: u8 = u8::MAX;
+
 
 pub const HOLE_CARDS_PER_PLAYER: u8 = 2;
 
+/// Hole cards per seat in Omaha
+pub const OMAHA_HOLE_CARDS: u8 = 4;
+
 
 pub const COMMUNITY_CARDS: u8 = 5;
 
-pub const TOTAL_CARDS_NEEDED: u8 = 15;
+/// Cards in a full deck
+pub const DECK_CARDS: u8 = 52;
+
+/// Cards in a short (6+) deck, deuces to fives removed
+pub const SHORT_DECK_CARDS: u8 = 36;
+
+/// Protocol fee ceiling (100%) in basis points
+pub const MAX_FEE_BPS: u16 = 10_000;
+
+/// Slots seated players have to challenge a proposed settlement (~1 minute)
+pub const DISPUTE_WINDOW_SLOTS: u64 = 150;
+
+/// Slots a player has to act once it is their turn (~30 seconds)
+pub const ACTION_TIMEOUT_SLOTS: u64 = 75;
+
+/// Time bank granted to each seat for every hand (~2 minutes), spent once
+/// a turn's deadline passes
+pub const TIME_BANK_SLOTS: u64 = 300;
+
+/// Slots after start_game before anyone may abandon a confidential hand
+/// that has no proposed result (~1 hour)
+pub const STUCK_HAND_SLOTS: u64 = 9_000;
 
 pub const SMALL_BLIND_MULTIPLIER: u64 = 1;
 pub const BIG_BLIND_MULTIPLIER: u64 = 2;
+
+/// Straddle as a multiple of the big blind
+pub const STRADDLE_MULTIPLIER: u64 = 2;
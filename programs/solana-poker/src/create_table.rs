@@ -1,34 +1,107 @@
 use anchor_lang::prelude::*;
-use crate::state::PokerTable;
+use crate::state::{
+    AccessMode, GameVariant, LobbyPage, PokerTable, ProtocolConfig, Straddle, TableAccess,
+};
 use crate::error::PokerError;
 use crate::constants::MAX_PLAYERS;
 
+/// Settings of a new table
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CreateTableParams {
+    /// Maximum players allowed (2-10)
+    pub max_players: u8,
+    /// Minimum buy-in in lamports
+    pub buy_in_min: u64,
+    /// Maximum buy-in in lamports
+    pub buy_in_max: u64,
+    /// Small blind amount in lamports
+    pub small_blind: u64,
+    /// Backend authority for game operations
+    pub backend_account: Pubkey,
+    /// Keep stacks and bets encrypted
+    pub confidential: bool,
+    /// Ante in lamports (0 for none)
+    pub ante: u64,
+    /// Big blind posts `ante` once for the whole table
+    pub big_blind_ante: bool,
+    /// Straddle position offered at every hand
+    pub straddle: Straddle,
+    /// Poker variant dealt at every hand
+    pub variant: GameVariant,
+    /// Who may join
+    pub access_mode: AccessMode,
+}
+
 /// Creates a new poker table with configuration
-pub fn handler(
-    ctx: Context<CreateTable>,
-    table_id: u64,
-    max_players: u8,
-    buy_in_min: u64,
-    buy_in_max: u64,
-    small_blind: u64,
-    backend_account: Pubkey,
-) -> Result<()> {
-    require!(max_players >= 2 && max_players <= MAX_PLAYERS, PokerError::InvalidBuyIn);
+///
+/// Antes, and the straddle when its seat opts in, are collected by
+/// start_game at every hand; with big_blind_ante the big blind posts
+/// `ante` once for the whole table.
+///
+/// `variant` picks the game dealt at every hand: Omaha deals four hole
+/// cards per seat and caps raises at the pot, short deck deals from 36
+/// cards (sixes up). Both seat at most 8 (see GameVariant::max_players).
+///
+/// `access_mode` restricts who may join (see join_table). The table's
+/// (empty) allowlist and bans are created alongside.
+///
+/// Confidential tables keep stacks and bets encrypted; their hands settle
+/// through settle_game_confidential and finalize_settlement_confidential,
+/// and players cash out through leave_table_confidential.
+pub fn handler(ctx: Context<CreateTable>, table_id: u64, params: CreateTableParams) -> Result<()> {
+    let CreateTableParams {
+        max_players,
+        buy_in_min,
+        buy_in_max,
+        small_blind,
+        backend_account,
+        confidential,
+        ante,
+        big_blind_ante,
+        straddle,
+        variant,
+        access_mode,
+    } = params;
+
+    require!((2..=MAX_PLAYERS).contains(&max_players), PokerError::InvalidBuyIn);
+    require!(max_players <= variant.max_players(), PokerError::InvalidBuyIn);
     require!(buy_in_min > 0 && buy_in_min <= buy_in_max, PokerError::InvalidBuyIn);
     require!(small_blind > 0, PokerError::InvalidBuyIn);
+    require!(!big_blind_ante || ante > 0, PokerError::InvalidBuyIn);
 
     let table = &mut ctx.accounts.table;
+    table.version = PokerTable::VERSION;
     table.creator = ctx.accounts.creator.key();
     table.backend = backend_account;
+    table.pending_backend = None;
     table.table_id = table_id;
     table.max_players = max_players;
     table.buy_in_min = buy_in_min;
     table.buy_in_max = buy_in_max;
     table.small_blind = small_blind;
+    table.ante = ante;
+    table.big_blind_ante = big_blind_ante;
+    table.straddle = straddle;
+    table.variant = variant;
     table.current_game = None;
     table.player_count = 0;
+    table.occupied_seats = 0;
+    table.confidential = confidential;
+    table.access_mode = access_mode;
     table.bump = ctx.bumps.table;
 
+    let table_access = &mut ctx.accounts.table_access;
+    table_access.version = TableAccess::VERSION;
+    table_access.table = table.key();
+    table_access.allowed = Vec::new();
+    table_access.banned = Vec::new();
+    table_access.bump = ctx.bumps.table_access;
+
+    // List the table in the lobby
+    let lobby_page = &mut ctx.accounts.lobby_page;
+    table.lobby_page = lobby_page.page_index;
+    lobby_page.insert(table.lobby_entry(table.key()))?;
+
     msg!("Poker table {} created by {} with backend {}", table_id, ctx.accounts.creator.key(), backend_account);
     Ok(())
 }
@@ -53,6 +126,31 @@ pub struct CreateTable<'info> {
     /// CHECK: This is a PDA vault that will hold SOL
     pub vault: AccountInfo<'info>,
 
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ PokerError::ProtocolPaused
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// Allowlist and bans of the table
+    #[account(
+        init,
+        payer = creator,
+        space = TableAccess::LEN,
+        seeds = [b"table_access", table.key().as_ref()],
+        bump
+    )]
+    pub table_access: Account<'info, TableAccess>,
+
+    /// Lobby page the table is listed on
+    #[account(
+        mut,
+        seeds = [b"lobby", lobby_page.page_index.to_le_bytes().as_ref()],
+        bump = lobby_page.bump
+    )]
+    pub lobby_page: Account<'info, LobbyPage>,
+
     /// Player creating the table
     #[account(mut)]
     pub creator: Signer<'info>,
@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+
+/// Size of one Ed25519SignatureOffsets entry in a precompile instruction
+const OFFSETS_LEN: usize = 14;
+
+/// Offsets start after the signature count and a padding byte
+const OFFSETS_START: usize = 2;
+
+/// Public keys that signed `message` through Ed25519 precompile
+/// instructions placed earlier in this transaction
+///
+/// The runtime has already verified every precompile signature, so only
+/// the signed data needs matching. Entries pointing at data in other
+/// instructions are ignored.
+pub fn signers_of(instructions: &AccountInfo, message: &[u8]) -> Result<Vec<Pubkey>> {
+    let current = load_current_index_checked(instructions)?;
+    let mut signers = Vec::new();
+
+    for index in 0..current {
+        let ix = load_instruction_at_checked(index as usize, instructions)?;
+        if ix.program_id != ed25519_program::ID {
+            continue;
+        }
+
+        let data = &ix.data;
+        let count = data.first().copied().unwrap_or(0) as usize;
+        for i in 0..count {
+            let start = OFFSETS_START + i * OFFSETS_LEN;
+            let Some(offsets) = data.get(start..start + OFFSETS_LEN) else {
+                break;
+            };
+            let read = |at: usize| u16::from_le_bytes([offsets[at], offsets[at + 1]]);
+
+            // signature, public key and message must all live in this instruction
+            if read(2) != u16::MAX || read(6) != u16::MAX || read(12) != u16::MAX {
+                continue;
+            }
+
+            let pubkey_offset = read(4) as usize;
+            let message_offset = read(8) as usize;
+            let message_len = read(10) as usize;
+            let (Some(pubkey), Some(signed)) = (
+                data.get(pubkey_offset..pubkey_offset + 32),
+                data.get(message_offset..message_offset + message_len),
+            ) else {
+                continue;
+            };
+
+            if signed == message {
+                let pubkey = Pubkey::try_from(pubkey)
+                    .map_err(|_| error!(crate::error::PokerError::MissingPlayerSignature))?;
+                signers.push(pubkey);
+            }
+        }
+    }
+
+    Ok(signers)
+}
@@ -50,7 +50,7 @@ pub enum PokerError {
     #[msg("Cards not processed yet")]
     CardsNotSubmitted,
 
-    #[msg("Cards not processed - all 8 batches must complete")]
+    #[msg("Cards not processed - all batches must complete")]
     CardsNotProcessed,
 
     #[msg("Invalid card count")]
@@ -83,7 +83,7 @@ pub enum PokerError {
     #[msg("Blinds already posted for this hand")]
     BlindsAlreadyPosted,
 
-    #[msg("Invalid batch index (must be 0, 1, or 2)")]
+    #[msg("Invalid batch index (batches must be processed in order)")]
     InvalidBatchIndex,
 
     #[msg("Cards already processed")]
@@ -97,4 +97,124 @@ pub enum PokerError {
 
     #[msg("Invalid refund accounts provided")]
     InvalidRefundAccounts,
+
+    #[msg("Only the table creator can perform this action")]
+    NotCreator,
+
+    #[msg("Signer is not the pending backend")]
+    NotPendingBackend,
+
+    #[msg("Table still has seated players")]
+    TableNotEmpty,
+
+    #[msg("Every seat at the table must be passed exactly once")]
+    InvalidSeatAccounts,
+
+    #[msg("Payouts must exactly distribute the pot")]
+    InvalidPayouts,
+
+    #[msg("Seat is not dealt into this game")]
+    SeatNotInGame,
+
+    #[msg("Lobby page is full")]
+    LobbyPageFull,
+
+    #[msg("Table is not listed on this lobby page")]
+    TableNotListed,
+
+    #[msg("Protocol is paused")]
+    ProtocolPaused,
+
+    #[msg("Invalid fee parameters")]
+    InvalidFee,
+
+    #[msg("Unsupported account version - run the matching migrate instruction")]
+    UnsupportedAccountVersion,
+
+    #[msg("Settlement dispute window is still open")]
+    DisputeWindowOpen,
+
+    #[msg("Settlement dispute window has closed")]
+    DisputeWindowClosed,
+
+    #[msg("Seat cannot challenge this settlement")]
+    InvalidChallenge,
+
+    #[msg("Challenge evidence does not contradict the proposed result")]
+    ChallengeRejected,
+
+    #[msg("A live player has not signed the settlement")]
+    MissingPlayerSignature,
+
+    #[msg("Action is not legal at this point of the hand")]
+    IllegalAction,
+
+    #[msg("Transcript has already reached the end of the hand")]
+    TranscriptComplete,
+
+    #[msg("Transcript has not reached the end of the hand")]
+    TranscriptIncomplete,
+
+    #[msg("Transcript agrees with the proposed settlement")]
+    TranscriptMatchesSettlement,
+
+    #[msg("Game account must be closed first")]
+    GameStillOpen,
+
+    #[msg("Card batch is waiting for confirm_cards_batch")]
+    BatchPending,
+
+    #[msg("No drawn card batch to confirm")]
+    NoBatchPending,
+
+    #[msg("Hand did not reach showdown")]
+    NotAtShowdown,
+
+    #[msg("Show or muck choice cannot be taken back")]
+    InvalidShowChoice,
+
+    #[msg("Cards can only be shown after folding or once the hand ends")]
+    HandNotOver,
+
+    #[msg("Player mucked their hand")]
+    HandMucked,
+
+    #[msg("Not available on confidential tables")]
+    ConfidentialTable,
+
+    #[msg("Only available on confidential tables")]
+    NotConfidentialTable,
+
+    #[msg("Run it twice needs an all-in agreement before the river")]
+    InvalidRunTwice,
+
+    #[msg("No player action is pending")]
+    NoActionPending,
+
+    #[msg("Player still has time to act")]
+    ActionNotExpired,
+
+    #[msg("Wallet is not invited to this table")]
+    NotInvited,
+
+    #[msg("Wallet is banned from this table")]
+    PlayerBanned,
+
+    #[msg("Table access list is full")]
+    AccessListFull,
+
+    #[msg("Betting has not reached this street")]
+    StreetNotReached,
+
+    #[msg("Unlisted tables need a lobby page to be listed on")]
+    LobbyPageRequired,
+
+    #[msg("A seat signed two different actions for the same turn")]
+    TranscriptEquivocated,
+
+    #[msg("Actions are not two different actions by one seat for the same turn")]
+    NotAnEquivocation,
+
+    #[msg("Neither the backend nor the player started the action clock")]
+    ActionClockNotStarted,
 }
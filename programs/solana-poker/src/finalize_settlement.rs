@@ -0,0 +1,129 @@
+use crate::error::PokerError;
+use crate::state::{GameStage, PlayerSeat, PokerGame, PokerTable, Settlement};
+use anchor_lang::prelude::*;
+
+/// Apply an unchallenged settlement and credit winnings to seat stacks
+///
+/// Permissionless once the dispute window has closed, so players do not
+/// depend on the backend to get paid. Pass every dealt-in seat via
+/// remaining_accounts, in any order.
+///
+/// Flow:
+/// 1. Validate the dispute window is over and, when the board ran twice,
+///    that each board pays half of every pot
+/// 2. Collect each seat's remaining bets from its chips and credit its payout
+/// 3. Clear table's current_game; the game and settlement accounts close
+///
+/// Lamports stay in the vault; players withdraw through leave_table.
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, FinalizeSettlement<'info>>) -> Result<()> {
+    let table = &mut ctx.accounts.table;
+    let game = &ctx.accounts.game;
+    let settlement = &ctx.accounts.settlement;
+
+    let clock = Clock::get()?;
+    require!(
+        clock.slot > settlement.deadline_slot,
+        PokerError::DisputeWindowOpen
+    );
+    require!(
+        ctx.remaining_accounts.len() == game.player_count as usize,
+        PokerError::InvalidSeatAccounts
+    );
+
+    // A voided hand hands every bet back and pays out no board
+    if game.run_twice && !settlement.voided {
+        Settlement::check_run_twice(
+            &settlement.total_bets,
+            &settlement.payouts,
+            &settlement.second_payouts,
+            settlement.dealt_mask & !settlement.folded_mask,
+        )?;
+    }
+
+    let table_key = table.key();
+    let mut seen_mask: u16 = 0;
+
+    for seat_info in ctx.remaining_accounts.iter() {
+        let mut seat: Account<PlayerSeat> = Account::try_from(seat_info)?;
+        require!(seat.version == PlayerSeat::VERSION, PokerError::UnsupportedAccountVersion);
+
+        let expected = Pubkey::create_program_address(
+            &[
+                b"player_seat",
+                table_key.as_ref(),
+                seat.player.as_ref(),
+                &[seat.bump],
+            ],
+            ctx.program_id,
+        )
+        .map_err(|_| PokerError::PlayerNotAtTable)?;
+        require!(expected == *seat_info.key, PokerError::PlayerNotAtTable);
+        require!(seat.game == game.key(), PokerError::SeatNotInGame);
+
+        let seat_bit = 1u16 << seat.seat_index;
+        require!(seen_mask & seat_bit == 0, PokerError::InvalidSeatAccounts);
+        seen_mask |= seat_bit;
+
+        // Checked against the stack when the result was proposed; stacks
+        // cannot change while the game is running
+        let idx = seat.seat_index as usize;
+        let additional = settlement.total_bets[idx]
+            .checked_sub(seat.total_bet)
+            .ok_or(PokerError::InvalidBetAmount)?;
+        let payout = settlement.payouts[idx];
+        seat.chips = seat
+            .chips
+            .checked_sub(additional)
+            .ok_or(PokerError::InsufficientChips)?
+            .checked_add(payout)
+            .ok_or(PokerError::InvalidPayouts)?;
+        seat.current_bet = 0;
+        seat.total_bet = 0;
+        seat.exit(&crate::ID)?;
+    }
+    require!(seen_mask == settlement.dealt_mask, PokerError::InvalidSeatAccounts);
+
+    // The game account closes to the backend; only the table keeps state
+    table.current_game = None;
+
+    msg!(
+        "Game {} finished. Winnings credited to {} seats",
+        game.game_id,
+        game.player_count
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct FinalizeSettlement<'info> {
+    #[account(
+        mut,
+        constraint = table.version == PokerTable::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub table: Account<'info, PokerTable>,
+
+    #[account(
+        mut,
+        close = backend,
+        constraint = game.table == table.key() @ PokerError::NoActiveGame,
+        constraint = game.stage == GameStage::PendingSettlement @ PokerError::InvalidGameStage,
+        constraint = game.version == PokerGame::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub game: Account<'info, PokerGame>,
+
+    #[account(
+        mut,
+        close = backend,
+        seeds = [b"settlement", game.key().as_ref()],
+        bump = settlement.bump
+    )]
+    pub settlement: Account<'info, Settlement>,
+
+    /// CHECK: Backend that paid for the game and settlement, receives their rent
+    #[account(
+        mut,
+        constraint = backend.key() == game.backend_account @ PokerError::NotBackend
+    )]
+    pub backend: AccountInfo<'info>,
+}
@@ -0,0 +1,151 @@
+use crate::constants::INCO_AUTHORITY_SEED;
+use crate::error::PokerError;
+use crate::inco_authority::top_up;
+use crate::state::{ConfidentialSettlement, GameStage, PlayerSeat, PokerGame, PokerTable};
+use anchor_lang::prelude::*;
+use inco_lightning::cpi::accounts::Operation;
+use inco_lightning::cpi::{e_add, e_select, e_sub};
+use inco_lightning::program::IncoLightning;
+
+/// Apply an unchallenged confidential settlement to the encrypted stacks
+///
+/// Permissionless once the dispute window has closed; the caller funds the
+/// Inco operations. Pass every dealt-in seat via remaining_accounts, in
+/// any order.
+///
+/// Flow:
+/// 1. Validate the dispute window is over
+/// 2. Each stack becomes stack - bet + payout when the result balanced,
+///    otherwise it is left as it was and the hand is void
+/// 3. Clear table's current_game; the game and settlement accounts close
+///
+/// Nothing is decrypted; lamports only move at leave_table_confidential.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, FinalizeSettlementConfidential<'info>>,
+) -> Result<()> {
+    let table = &mut ctx.accounts.table;
+    let game = &ctx.accounts.game;
+    let settlement = &ctx.accounts.settlement;
+
+    let clock = Clock::get()?;
+    require!(
+        clock.slot > settlement.deadline_slot,
+        PokerError::DisputeWindowOpen
+    );
+    require!(
+        ctx.remaining_accounts.len() == game.player_count as usize,
+        PokerError::InvalidSeatAccounts
+    );
+
+    let cpi_program = ctx.accounts.inco_lightning_program.to_account_info();
+    let authority = ctx.accounts.inco_authority.to_account_info();
+    let payer = ctx.accounts.payer.to_account_info();
+    let system_program = ctx.accounts.system_program.to_account_info();
+    let seeds = &[INCO_AUTHORITY_SEED, &[ctx.bumps.inco_authority]];
+    let signer = &[&seeds[..]];
+
+    let op_accounts = Operation {
+        signer: authority.clone(),
+    };
+    let op = || CpiContext::new_with_signer(cpi_program.clone(), op_accounts.clone(), signer);
+    top_up(&authority, &payer, &system_program)?;
+
+    let table_key = table.key();
+    let mut seen_mask: u16 = 0;
+
+    for seat_info in ctx.remaining_accounts.iter() {
+        let mut seat: Account<PlayerSeat> = Account::try_from(seat_info)?;
+        require!(seat.version == PlayerSeat::VERSION, PokerError::UnsupportedAccountVersion);
+
+        let expected = Pubkey::create_program_address(
+            &[
+                b"player_seat",
+                table_key.as_ref(),
+                seat.player.as_ref(),
+                &[seat.bump],
+            ],
+            ctx.program_id,
+        )
+        .map_err(|_| PokerError::PlayerNotAtTable)?;
+        require!(expected == *seat_info.key, PokerError::PlayerNotAtTable);
+        require!(seat.game == game.key(), PokerError::SeatNotInGame);
+
+        let seat_bit = 1u16 << seat.seat_index;
+        require!(seen_mask & seat_bit == 0, PokerError::InvalidSeatAccounts);
+        seen_mask |= seat_bit;
+
+        // Bets were capped at the stack when the result was proposed
+        let idx = seat.seat_index as usize;
+        let left = e_sub(op(), seat.enc_chips, settlement.total_bets[idx], 0)?;
+        let won = e_add(op(), left, settlement.payouts[idx], 0)?;
+        seat.enc_chips = e_select(op(), settlement.balanced, won, seat.enc_chips, 0)?;
+        seat.current_bet = 0;
+        seat.total_bet = 0;
+        seat.exit(&crate::ID)?;
+    }
+    require!(seen_mask == settlement.dealt_mask, PokerError::InvalidSeatAccounts);
+
+    top_up(&authority, &payer, &system_program)?;
+
+    // The game account closes to the backend; only the table keeps state
+    table.current_game = None;
+
+    msg!(
+        "Game {} settled confidentially for {} seats",
+        game.game_id,
+        game.player_count
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct FinalizeSettlementConfidential<'info> {
+    #[account(
+        mut,
+        constraint = table.confidential @ PokerError::NotConfidentialTable,
+        constraint = table.version == PokerTable::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub table: Account<'info, PokerTable>,
+
+    #[account(
+        mut,
+        close = backend,
+        constraint = game.table == table.key() @ PokerError::NoActiveGame,
+        constraint = game.stage == GameStage::PendingSettlement @ PokerError::InvalidGameStage,
+        constraint = game.version == PokerGame::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub game: Account<'info, PokerGame>,
+
+    #[account(
+        mut,
+        close = backend,
+        seeds = [b"confidential_settlement", game.key().as_ref()],
+        bump = settlement.bump,
+        constraint = settlement.version == ConfidentialSettlement::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub settlement: Account<'info, ConfidentialSettlement>,
+
+    /// CHECK: Backend that paid for the game and settlement, receives their rent
+    #[account(
+        mut,
+        constraint = backend.key() == game.backend_account @ PokerError::NotBackend
+    )]
+    pub backend: AccountInfo<'info>,
+
+    /// Anyone; funds the Inco operations
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Program PDA that owns the stack handles
+    #[account(
+        mut,
+        seeds = [INCO_AUTHORITY_SEED],
+        bump
+    )]
+    pub inco_authority: AccountInfo<'info>,
+
+    pub inco_lightning_program: Program<'info, IncoLightning>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,311 @@
+//! Hold'em, Omaha and short-deck hand evaluation for settlement challenges
+//!
+//! Cards are indices 0..52 (a decrypted card plaintext % 52):
+//! rank = index % 13 (0 = deuce .. 12 = ace), suit = index / 13.
+//! Short-deck plaintexts are dealt % 36 and mapped onto the same indices
+//! by short_deck_card_index.
+
+use crate::constants::SHORT_DECK_CARDS;
+
+/// Hand categories, weakest first
+const HIGH_CARD: u32 = 0;
+const PAIR: u32 = 1;
+const TWO_PAIR: u32 = 2;
+const TRIPS: u32 = 3;
+const STRAIGHT: u32 = 4;
+const FLUSH: u32 = 5;
+const FULL_HOUSE: u32 = 6;
+const QUADS: u32 = 7;
+const STRAIGHT_FLUSH: u32 = 8;
+
+/// Lowest rank of the short deck (the six)
+const SHORT_DECK_LOW: u8 = 4;
+
+/// Card index from a decrypted card plaintext
+pub fn card_index(plaintext: u128) -> u8 {
+    (plaintext % 52) as u8
+}
+
+/// Card index from a decrypted short-deck plaintext
+///
+/// The 36-card deck is dealt as plaintext % 36 with rank = value % 9
+/// (0 = six .. 8 = ace) and suit = value / 9.
+pub fn short_deck_card_index(plaintext: u128) -> u8 {
+    let value = (plaintext % SHORT_DECK_CARDS as u128) as u8;
+    (value / 9) * 13 + value % 9 + SHORT_DECK_LOW
+}
+
+/// Score of the best five-card hand among `cards`
+///
+/// Higher scores win; equal scores split. The category sits above five
+/// 4-bit tiebreak ranks.
+pub fn evaluate(cards: &[u8]) -> u32 {
+    evaluate_ranked(cards, false)
+}
+
+/// Score of the best short-deck hand among `cards`
+///
+/// A flush beats a full house, and the ace also plays low in A-6-7-8-9.
+pub fn evaluate_short_deck(cards: &[u8]) -> u32 {
+    evaluate_ranked(cards, true)
+}
+
+fn evaluate_ranked(cards: &[u8], short_deck: bool) -> u32 {
+    // Short deck swaps the flush and full house categories
+    let (flush, full_house) = if short_deck {
+        (FULL_HOUSE, FLUSH)
+    } else {
+        (FLUSH, FULL_HOUSE)
+    };
+    let low = if short_deck { SHORT_DECK_LOW } else { 0 };
+
+    let mut rank_counts = [0u8; 13];
+    let mut suit_masks = [0u16; 4];
+    for &card in cards {
+        let (rank, suit) = (card % 13, card / 13);
+        rank_counts[rank as usize] += 1;
+        suit_masks[suit as usize % 4] |= 1 << rank;
+    }
+
+    if let Some(&mask) = suit_masks.iter().find(|m| m.count_ones() >= 5) {
+        if let Some(high) = straight_high(mask, low) {
+            return score(STRAIGHT_FLUSH, &[high]);
+        }
+        return score(flush, &top_ranks(mask, 5));
+    }
+
+    // Ranks grouped by multiplicity, highest rank first within a group
+    let mut quads = Vec::new();
+    let mut trips = Vec::new();
+    let mut pairs = Vec::new();
+    let mut singles = Vec::new();
+    let mut rank_mask: u16 = 0;
+    for rank in (0..13u8).rev() {
+        match rank_counts[rank as usize] {
+            0 => continue,
+            1 => singles.push(rank),
+            2 => pairs.push(rank),
+            3 => trips.push(rank),
+            _ => quads.push(rank),
+        }
+        rank_mask |= 1 << rank;
+    }
+
+    if let Some(&quad) = quads.first() {
+        let kicker = (0..13u8)
+            .rev()
+            .find(|&r| r != quad && rank_counts[r as usize] > 0);
+        return score(QUADS, &[quad, kicker.unwrap_or(0)]);
+    }
+    if let Some(&trip) = trips.first() {
+        // A second set of trips plays as the pair
+        let pair = trips.get(1).copied().into_iter().chain(pairs.first().copied()).max();
+        if let Some(pair) = pair {
+            return score(full_house, &[trip, pair]);
+        }
+    }
+    if let Some(high) = straight_high(rank_mask, low) {
+        return score(STRAIGHT, &[high]);
+    }
+    if let Some(&trip) = trips.first() {
+        return score(TRIPS, &kickers(&[trip], &rank_counts, 2));
+    }
+    if pairs.len() >= 2 {
+        return score(TWO_PAIR, &kickers(&[pairs[0], pairs[1]], &rank_counts, 1));
+    }
+    if let Some(&pair) = pairs.first() {
+        return score(PAIR, &kickers(&[pair], &rank_counts, 3));
+    }
+    score(HIGH_CARD, &top_ranks(rank_mask, 5))
+}
+
+/// Score of the best Omaha hand: exactly two of `hole` plus exactly three
+/// of `board`
+pub fn evaluate_omaha(hole: &[u8], board: &[u8]) -> u32 {
+    let mut best = 0;
+    for (i, &h1) in hole.iter().enumerate() {
+        for &h2 in &hole[i + 1..] {
+            for a in 0..board.len() {
+                for b in a + 1..board.len() {
+                    for c in b + 1..board.len() {
+                        best = best.max(evaluate(&[h1, h2, board[a], board[b], board[c]]));
+                    }
+                }
+            }
+        }
+    }
+    best
+}
+
+/// Highest rank of a five-rank run in `mask`, counting the wheel (A-5,
+/// or A-9 when the deck's lowest rank is `low`)
+fn straight_high(mask: u16, low: u8) -> Option<u8> {
+    // Ace also plays low, just below the lowest rank
+    let extended = (mask << 1) | (((mask >> 12) & 1) << low);
+    (4..14u8)
+        .rev()
+        .find(|&top| (extended >> (top - 4)) & 0b11111 == 0b11111)
+        .map(|top| top - 1)
+}
+
+/// The `count` highest ranks set in `mask`
+fn top_ranks(mask: u16, count: usize) -> Vec<u8> {
+    (0..13u8)
+        .rev()
+        .filter(|&r| mask & (1 << r) != 0)
+        .take(count)
+        .collect()
+}
+
+/// `made` ranks followed by the best `count` unused kickers
+fn kickers(made: &[u8], rank_counts: &[u8; 13], count: usize) -> Vec<u8> {
+    let mut ranks = made.to_vec();
+    ranks.extend(
+        (0..13u8)
+            .rev()
+            .filter(|r| rank_counts[*r as usize] > 0 && !made.contains(r))
+            .take(count),
+    );
+    ranks
+}
+
+fn score(category: u32, ranks: &[u8]) -> u32 {
+    let mut value = category;
+    for i in 0..5 {
+        value = (value << 4) | ranks.get(i).map_or(0, |&r| r as u32 + 1);
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Card indices from "As Kd Tc 2h": rank then suit (c, d, h, s)
+    fn cards(hand: &str) -> Vec<u8> {
+        hand.split_whitespace()
+            .map(|card| {
+                let mut chars = card.chars();
+                let rank = "23456789TJQKA".find(chars.next().unwrap()).unwrap() as u8;
+                let suit = "cdhs".find(chars.next().unwrap()).unwrap() as u8;
+                suit * 13 + rank
+            })
+            .collect()
+    }
+
+    fn category(score: u32) -> u32 {
+        score >> 20
+    }
+
+    #[test]
+    fn categories_rank_in_order() {
+        let hands = [
+            "2c 7d 9h Js Kc",
+            "2c 2d 9h Js Kc",
+            "2c 2d 9h 9s Kc",
+            "2c 2d 2h Js Kc",
+            "5c 6d 7h 8s 9c",
+            "2c 7c 9c Jc Kc",
+            "2c 2d 2h Ks Kc",
+            "2c 2d 2h 2s Kc",
+            "5c 6c 7c 8c 9c",
+        ];
+        let scores: Vec<u32> = hands.iter().map(|hand| evaluate(&cards(hand))).collect();
+        for (expected, &score) in scores.iter().enumerate() {
+            assert_eq!(category(score), expected as u32);
+        }
+        assert!(scores.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn best_five_of_seven() {
+        // The board's flush beats the pocket pair's trips
+        let score = evaluate(&cards("Ah Kh 9h 4h 2c 2d 2h"));
+        assert_eq!(category(score), FLUSH);
+    }
+
+    #[test]
+    fn wheel_is_the_lowest_straight() {
+        let wheel = evaluate(&cards("Ac 2d 3h 4s 5c"));
+        let six_high = evaluate(&cards("2c 3d 4h 5s 6c"));
+        assert_eq!(category(wheel), STRAIGHT);
+        assert!(wheel < six_high);
+
+        let steel_wheel = evaluate(&cards("Ac 2c 3c 4c 5c Kd Qh"));
+        assert_eq!(category(steel_wheel), STRAIGHT_FLUSH);
+
+        // No wrap-around through the ace
+        assert_eq!(category(evaluate(&cards("Qc Kd Ah 2s 3c"))), HIGH_CARD);
+    }
+
+    #[test]
+    fn kickers_break_ties() {
+        let ace_king = evaluate(&cards("Ac Ad Kh 7s 4c"));
+        let ace_queen = evaluate(&cards("Ac Ad Qh 7s 4c"));
+        assert!(ace_king > ace_queen);
+
+        // Only the best five cards count: the sixth and seventh never play
+        let board = "Ac Ad Kh Qs Jc";
+        let first = evaluate(&cards(&format!("{board} 3d 2h")));
+        let second = evaluate(&cards(&format!("{board} 4d 3h")));
+        assert_eq!(first, second);
+
+        // Two pair compares the second pair, then the kicker
+        let kings_up = evaluate(&cards("Kc Kd 9h 9s 2c"));
+        let kings_eights = evaluate(&cards("Kc Kd 8h 8s Ac"));
+        assert!(kings_up > kings_eights);
+        let queen_kicker = evaluate(&cards("Kc Kd 9h 9s Qc"));
+        assert!(queen_kicker > kings_up);
+    }
+
+    #[test]
+    fn omaha_plays_exactly_two_hole_cards() {
+        // Four spades on board and one in hand is no flush
+        let hole = cards("As Kd 7c 2h");
+        let board = cards("Qs Js 9s 3s 8d");
+        assert_ne!(category(evaluate_omaha(&hole, &board)), FLUSH);
+        assert_eq!(category(evaluate(&[hole.clone(), board.clone()].concat())), FLUSH);
+
+        // A full house on board still needs two hole cards
+        let hole = cards("2c 3d 4h 7s");
+        let board = cards("Ac Ad Ah Ks Kc");
+        assert_eq!(category(evaluate_omaha(&hole, &board)), TRIPS);
+
+        // Two suited hole cards make the flush with three on board
+        let hole = cards("As Ks 7c 2h");
+        assert_eq!(category(evaluate_omaha(&hole, &cards("Qs 9s 3s 8d 4c"))), FLUSH);
+    }
+
+    #[test]
+    fn short_deck_flush_beats_full_house() {
+        let flush = cards("6c 8c 9c Jc Kc");
+        let full_house = cards("6c 6d 6h Ks Kc");
+        assert!(evaluate(&full_house) > evaluate(&flush));
+        assert!(evaluate_short_deck(&flush) > evaluate_short_deck(&full_house));
+        assert!(evaluate_short_deck(&cards("6c 6d 6h 6s Kc")) > evaluate_short_deck(&flush));
+    }
+
+    #[test]
+    fn short_deck_ace_plays_below_the_six() {
+        let low_straight = evaluate_short_deck(&cards("Ac 6d 7h 8s 9c"));
+        let ten_high = evaluate_short_deck(&cards("6d 7h 8s 9c Tc"));
+        assert_eq!(category(low_straight), STRAIGHT);
+        assert!(low_straight < ten_high);
+        assert_eq!(
+            category(evaluate_short_deck(&cards("Ac 6c 7c 8c 9c"))),
+            STRAIGHT_FLUSH
+        );
+
+        // A-5 is no straight without the small cards
+        assert_ne!(category(evaluate(&cards("Ac 6d 7h 8s 9c"))), STRAIGHT);
+    }
+
+    #[test]
+    fn short_deck_plaintexts_map_to_sixes_up() {
+        assert_eq!(short_deck_card_index(0), cards("6c")[0]);
+        assert_eq!(short_deck_card_index(8), cards("Ac")[0]);
+        assert_eq!(short_deck_card_index(9), cards("6d")[0]);
+        assert_eq!(short_deck_card_index(35), cards("As")[0]);
+        assert_eq!(short_deck_card_index(36), short_deck_card_index(0));
+    }
+}
@@ -0,0 +1,30 @@
+use crate::constants::INCO_AUTHORITY_FLOAT;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+/// Top the Inco authority PDA back up to INCO_AUTHORITY_FLOAT
+///
+/// The PDA signs Inco CPIs and pays for the accounts they create, so
+/// callers refill it before those CPIs (first use) and after them (their
+/// own usage).
+pub fn top_up<'info>(
+    authority: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let balance = authority.lamports();
+    if balance >= INCO_AUTHORITY_FLOAT {
+        return Ok(());
+    }
+
+    system_program::transfer(
+        CpiContext::new(
+            system_program.clone(),
+            system_program::Transfer {
+                from: payer.clone(),
+                to: authority.clone(),
+            },
+        ),
+        INCO_AUTHORITY_FLOAT - balance,
+    )
+}
@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+use crate::state::LobbyPage;
+
+/// Creates an empty lobby page
+///
+/// Permissionless: anyone can pay for the next page once earlier ones fill up.
+pub fn handler(ctx: Context<InitLobbyPage>, page_index: u32) -> Result<()> {
+    let lobby_page = &mut ctx.accounts.lobby_page;
+    lobby_page.page_index = page_index;
+    lobby_page.entries = Vec::new();
+    lobby_page.bump = ctx.bumps.lobby_page;
+
+    msg!("Lobby page {} created", page_index);
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(page_index: u32)]
+pub struct InitLobbyPage<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = LobbyPage::LEN,
+        seeds = [b"lobby", page_index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub lobby_page: Account<'info, LobbyPage>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
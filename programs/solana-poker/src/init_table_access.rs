@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+use crate::state::{PokerTable, TableAccess};
+use crate::error::PokerError;
+
+/// Creates the empty allowlist and bans of a table created before they
+/// existed
+///
+/// Permissionless: the lists start empty, the payer only covers the rent.
+/// join_table needs this account, so run it after migrate_table.
+pub fn handler(ctx: Context<InitTableAccess>) -> Result<()> {
+    let table_access = &mut ctx.accounts.table_access;
+    table_access.version = TableAccess::VERSION;
+    table_access.table = ctx.accounts.table.key();
+    table_access.allowed = Vec::new();
+    table_access.banned = Vec::new();
+    table_access.bump = ctx.bumps.table_access;
+
+    msg!("Access lists created for table {}", ctx.accounts.table.table_id);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitTableAccess<'info> {
+    #[account(
+        constraint = table.version == PokerTable::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub table: Account<'info, PokerTable>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = TableAccess::LEN,
+        seeds = [b"table_access", table.key().as_ref()],
+        bump
+    )]
+    pub table_access: Account<'info, TableAccess>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
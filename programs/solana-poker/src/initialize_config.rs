@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+use crate::constants::MAX_FEE_BPS;
+use crate::error::PokerError;
+use crate::program::SolanaPoker;
+use crate::state::ProtocolConfig;
+
+/// Creates the protocol config singleton
+///
+/// Only the program's upgrade authority can initialize it; it becomes the
+/// first protocol admin.
+pub fn handler(
+    ctx: Context<InitializeConfig>,
+    treasury: Pubkey,
+    fee_bps: u16,
+    fee_cap: u64,
+) -> Result<()> {
+    require!(fee_bps <= MAX_FEE_BPS, PokerError::InvalidFee);
+
+    let config = &mut ctx.accounts.config;
+    config.admin = ctx.accounts.admin.key();
+    config.treasury = treasury;
+    config.fee_bps = fee_bps;
+    config.fee_cap = fee_cap;
+    config.paused = false;
+    config.bump = ctx.bumps.config;
+
+    msg!(
+        "Protocol config initialized: admin={} treasury={} fee_bps={} fee_cap={}",
+        config.admin,
+        treasury,
+        fee_bps,
+        fee_cap
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = ProtocolConfig::LEN,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key()) @ PokerError::NotAdmin
+    )]
+    pub program: Program<'info, SolanaPoker>,
+
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key()) @ PokerError::NotAdmin
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -1,12 +1,42 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
 use anchor_lang::system_program;
-use crate::state::{PokerTable, PlayerSeat};
+use crate::state::{
+    AccessMode, LobbyPage, PokerTable, PlayerSeat, ProtocolConfig, ShowChoice, TableAccess,
+};
+use crate::ed25519::signers_of;
 use crate::error::PokerError;
+use crate::constants::TIME_BANK_SLOTS;
+use inco_lightning::types::Euint128;
 
 /// Player joins a table with a buy-in
+///
+/// Banned wallets are always refused. Allowlist tables admit the creator
+/// and allowlisted wallets; Invite tables also admit a wallet carrying the
+/// creator's signature over TableAccess::invite_digest(table, wallet), as
+/// an Ed25519 precompile instruction earlier in the transaction. An invite
+/// only names its own wallet, so seeing it on-chain admits no one else;
+/// the creator withdraws one by banning the wallet.
 pub fn handler(ctx: Context<JoinTable>, buy_in: u64) -> Result<()> {
     let table = &mut ctx.accounts.table;
     let player_seat = &mut ctx.accounts.player_seat;
+    let table_access = &ctx.accounts.table_access;
+    let player = ctx.accounts.player.key();
+
+    // Enforce the table's access rules
+    require!(!table_access.is_banned(&player), PokerError::PlayerBanned);
+    let invited = player == table.creator || table_access.is_allowed(&player);
+    let admitted = match table.access_mode {
+        AccessMode::Public => true,
+        AccessMode::Allowlist => invited,
+        AccessMode::Invite => {
+            invited || {
+                let digest = TableAccess::invite_digest(&table.key(), &player);
+                signers_of(&ctx.accounts.instructions, &digest)?.contains(&table.creator)
+            }
+        }
+    };
+    require!(admitted, PokerError::NotInvited);
     
     // Validate buy-in amount
     require!(
@@ -33,19 +63,33 @@ pub fn handler(ctx: Context<JoinTable>, buy_in: u64) -> Result<()> {
     )?;
 
     // Initialize PlayerSeat
+    player_seat.version = PlayerSeat::VERSION;
     player_seat.game = Pubkey::default(); // Will be set/used later
     player_seat.player = ctx.accounts.player.key();
-    player_seat.seat_index = table.player_count; // Assign next available seat
+    player_seat.seat_index = table.first_free_seat().ok_or(PokerError::TableFull)?;
     player_seat.chips = buy_in;
+    player_seat.enc_chips = Euint128::default();
     player_seat.current_bet = 0;
     player_seat.total_bet = 0;
     player_seat.is_folded = false;
     player_seat.is_all_in = false;
     player_seat.has_acted = false;
     player_seat.hand_rank = 0;
+    player_seat.sitting_out = false;
+    player_seat.missed_small_blind = false;
+    player_seat.missed_big_blind = false;
+    player_seat.show_choice = ShowChoice::Undecided;
+    player_seat.time_bank = TIME_BANK_SLOTS;
+    player_seat.clock_opt_in = false;
+    player_seat.straddle_opt_in = false;
     player_seat.bump = ctx.bumps.player_seat;
 
     table.player_count += 1;
+    table.occupied_seats |= 1 << player_seat.seat_index;
+
+    ctx.accounts
+        .lobby_page
+        .set_player_count(table.key(), table.player_count)?;
 
     msg!(
         "Player {} joined table at seat {} with {} lamports. Players: {}/{}",
@@ -61,9 +105,19 @@ pub fn handler(ctx: Context<JoinTable>, buy_in: u64) -> Result<()> {
 
 #[derive(Accounts)]
 pub struct JoinTable<'info> {
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = table.version == PokerTable::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
     pub table: Account<'info, PokerTable>,
 
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ PokerError::ProtocolPaused
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
     /// CHECK: Vault PDA to receive SOL
     #[account(
         mut,
@@ -72,6 +126,20 @@ pub struct JoinTable<'info> {
     )]
     pub vault: AccountInfo<'info>,
 
+    #[account(
+        mut,
+        seeds = [b"lobby", table.lobby_page.to_le_bytes().as_ref()],
+        bump = lobby_page.bump
+    )]
+    pub lobby_page: Account<'info, LobbyPage>,
+
+    #[account(
+        seeds = [b"table_access", table.key().as_ref()],
+        bump = table_access.bump,
+        constraint = table_access.version == TableAccess::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub table_access: Account<'info, TableAccess>,
+
     #[account(
         init,
         payer = player,
@@ -84,5 +152,9 @@ pub struct JoinTable<'info> {
     #[account(mut)]
     pub player: Signer<'info>,
 
+    /// CHECK: Instructions sysvar holding the creator's signed invite
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+
     pub system_program: Program<'info, System>,
 }
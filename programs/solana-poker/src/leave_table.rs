@@ -0,0 +1,91 @@
+use anchor_lang::prelude::*;
+use crate::state::{LobbyPage, PokerTable, PlayerSeat};
+use crate::error::PokerError;
+
+/// Player cashes out their stack and leaves the table
+///
+/// Flow:
+/// 1. Validate no game is running
+/// 2. Transfer the seat's chips from the vault to the player
+/// 3. Close the seat (rent back to the player) and free the seat index
+pub fn handler(ctx: Context<LeaveTable>) -> Result<()> {
+    let table = &mut ctx.accounts.table;
+    let player_seat = &ctx.accounts.player_seat;
+
+    require!(table.current_game.is_none(), PokerError::CannotLeaveDuringGame);
+
+    let amount = player_seat.chips;
+    let table_key = table.key();
+    let seeds = &[b"vault", table_key.as_ref(), &[ctx.bumps.vault]];
+    let signer = &[&seeds[..]];
+
+    if amount > 0 {
+        let cpi_accounts = anchor_lang::system_program::Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.player.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.system_program.to_account_info();
+
+        anchor_lang::system_program::transfer(
+            CpiContext::new_with_signer(cpi_program, cpi_accounts, signer),
+            amount,
+        )?;
+    }
+
+    table.player_count = table.player_count.saturating_sub(1);
+    table.occupied_seats &= !(1 << player_seat.seat_index);
+
+    ctx.accounts
+        .lobby_page
+        .set_player_count(table.key(), table.player_count)?;
+
+    msg!(
+        "Player {} left seat {} with {} lamports. Players: {}/{}",
+        ctx.accounts.player.key(),
+        player_seat.seat_index,
+        amount,
+        table.player_count,
+        table.max_players
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct LeaveTable<'info> {
+    #[account(
+        mut,
+        constraint = !table.confidential @ PokerError::ConfidentialTable,
+        constraint = table.version == PokerTable::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub table: Account<'info, PokerTable>,
+
+    /// CHECK: Vault PDA to pay out from
+    #[account(
+        mut,
+        seeds = [b"vault", table.key().as_ref()],
+        bump
+    )]
+    pub vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"lobby", table.lobby_page.to_le_bytes().as_ref()],
+        bump = lobby_page.bump
+    )]
+    pub lobby_page: Account<'info, LobbyPage>,
+
+    #[account(
+        mut,
+        close = player,
+        seeds = [b"player_seat", table.key().as_ref(), player.key().as_ref()],
+        bump = player_seat.bump,
+        constraint = player_seat.version == PlayerSeat::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub player_seat: Account<'info, PlayerSeat>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
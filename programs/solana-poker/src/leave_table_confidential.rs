@@ -0,0 +1,124 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use inco_lightning::program::IncoLightning;
+use crate::attestation::verify_decryptions;
+use crate::state::{LobbyPage, PokerTable, PlayerSeat};
+use crate::error::PokerError;
+
+/// Player cashes out a confidential stack and leaves the table
+///
+/// The player passes the attested decryption of their enc_chips (see
+/// reveal_stack); the transaction must carry the Ed25519 instructions from
+/// the attested decrypt. This is the only point where the stack is
+/// revealed.
+///
+/// Flow:
+/// 1. Validate no game is running
+/// 2. Verify the decrypted stack against the seat's enc_chips handle
+/// 3. Transfer the stack plus any unsettled deposit from the vault
+/// 4. Close the seat (rent back to the player) and free the seat index
+pub fn handler(ctx: Context<LeaveTableConfidential>, stack: u128) -> Result<()> {
+    let table = &mut ctx.accounts.table;
+    let player_seat = &ctx.accounts.player_seat;
+
+    require!(table.current_game.is_none(), PokerError::CannotLeaveDuringGame);
+
+    // Seats that never finished a confidential hand hold only deposits
+    let settled = if player_seat.enc_chips.0 == 0 {
+        require!(stack == 0, PokerError::InvalidBetAmount);
+        0
+    } else {
+        verify_decryptions(
+            ctx.accounts.inco_lightning_program.to_account_info(),
+            ctx.accounts.instructions.to_account_info(),
+            ctx.accounts.player.to_account_info(),
+            &[player_seat.enc_chips],
+            &[stack],
+        )?;
+        u64::try_from(stack).map_err(|_| PokerError::InvalidBetAmount)?
+    };
+
+    let amount = player_seat
+        .chips
+        .checked_add(settled)
+        .ok_or(PokerError::InvalidBetAmount)?;
+    let table_key = table.key();
+    let seeds = &[b"vault", table_key.as_ref(), &[ctx.bumps.vault]];
+    let signer = &[&seeds[..]];
+
+    if amount > 0 {
+        let cpi_accounts = anchor_lang::system_program::Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.player.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.system_program.to_account_info();
+
+        anchor_lang::system_program::transfer(
+            CpiContext::new_with_signer(cpi_program, cpi_accounts, signer),
+            amount,
+        )?;
+    }
+
+    table.player_count = table.player_count.saturating_sub(1);
+    table.occupied_seats &= !(1 << player_seat.seat_index);
+
+    ctx.accounts
+        .lobby_page
+        .set_player_count(table.key(), table.player_count)?;
+
+    msg!(
+        "Player {} left seat {} with {} lamports. Players: {}/{}",
+        ctx.accounts.player.key(),
+        player_seat.seat_index,
+        amount,
+        table.player_count,
+        table.max_players
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct LeaveTableConfidential<'info> {
+    #[account(
+        mut,
+        constraint = table.confidential @ PokerError::NotConfidentialTable,
+        constraint = table.version == PokerTable::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub table: Account<'info, PokerTable>,
+
+    /// CHECK: Vault PDA to pay out from
+    #[account(
+        mut,
+        seeds = [b"vault", table.key().as_ref()],
+        bump
+    )]
+    pub vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"lobby", table.lobby_page.to_le_bytes().as_ref()],
+        bump = lobby_page.bump
+    )]
+    pub lobby_page: Account<'info, LobbyPage>,
+
+    #[account(
+        mut,
+        close = player,
+        seeds = [b"player_seat", table.key().as_ref(), player.key().as_ref()],
+        bump = player_seat.bump,
+        constraint = player_seat.version == PlayerSeat::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub player_seat: Account<'info, PlayerSeat>,
+
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    /// CHECK: Instructions sysvar holding the attestation Ed25519 instructions
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+
+    pub inco_lightning_program: Program<'info, IncoLightning>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -1,30 +1,118 @@
 #![allow(unexpected_cfgs)]
+// Anchor 0.31's #[program] still expands to the deprecated AccountInfo::realloc
+#![allow(deprecated)]
 
 use anchor_lang::prelude::*;
 
+pub mod attestation;
 pub mod constants;
+pub mod ed25519;
 pub mod error;
+pub mod hand_eval;
+pub mod inco_authority;
 pub mod state;
 
+pub mod accept_backend;
+pub mod add_chips;
+pub mod adjudicate_transcript;
+pub mod agree_run_it_twice;
+pub mod arm_action_clock;
+pub mod challenge_settlement;
+pub mod challenge_settlement_confidential;
+pub mod close_table;
+pub mod close_transcript;
+pub mod confirm_cards_batch;
 pub mod create_table;
+pub mod finalize_settlement;
+pub mod finalize_settlement_confidential;
+pub mod init_lobby_page;
+pub mod init_table_access;
+pub mod initialize_config;
 pub mod join_table;
+pub mod leave_table;
+pub mod leave_table_confidential;
+pub mod migrate_game;
+pub mod migrate_seat;
+pub mod migrate_table;
+pub mod open_challenge_evidence;
+pub mod open_transcript;
 pub mod refund_all;
+pub mod refund_hand_confidential;
 pub mod process_cards;
-pub mod reveal_card_offset;
+pub mod propose_backend;
+pub mod prove_equivocation;
 pub mod reveal_community;
+pub mod reveal_duplicate_flag;
 pub mod reveal_hand;
+pub mod reveal_showdown;
+pub mod reveal_stack;
+pub mod reveal_street;
+pub mod set_access_mode;
+pub mod set_action_clock;
+pub mod set_paused;
+pub mod set_show_choice;
+pub mod set_sitting_out;
+pub mod set_straddle;
 pub mod settle_game;
+pub mod settle_game_confidential;
+pub mod settle_game_signed;
 pub mod start_game;
+pub mod submit_actions;
+pub mod timeout_action;
+pub mod update_config;
+pub mod update_table_access;
 
+use accept_backend::*;
+use add_chips::*;
+use adjudicate_transcript::*;
+use agree_run_it_twice::*;
+use arm_action_clock::*;
+use challenge_settlement::*;
+use challenge_settlement_confidential::*;
+use close_table::*;
+use close_transcript::*;
+use confirm_cards_batch::*;
 use create_table::*;
+use finalize_settlement::*;
+use finalize_settlement_confidential::*;
+use init_lobby_page::*;
+use init_table_access::*;
+use initialize_config::*;
 use join_table::*;
+use leave_table::*;
+use leave_table_confidential::*;
+use migrate_game::*;
+use migrate_seat::*;
+use migrate_table::*;
+use open_challenge_evidence::*;
+use open_transcript::*;
 use refund_all::*;
+use refund_hand_confidential::*;
 use process_cards::*;
-use reveal_card_offset::*;
+use propose_backend::*;
+use prove_equivocation::*;
 use reveal_community::*;
+use reveal_duplicate_flag::*;
 use reveal_hand::*;
+use reveal_showdown::*;
+use reveal_stack::*;
+use reveal_street::*;
+use set_access_mode::*;
+use set_action_clock::*;
+use set_paused::*;
+use set_show_choice::*;
+use set_sitting_out::*;
+use set_straddle::*;
 use settle_game::*;
+use settle_game_confidential::*;
+use settle_game_signed::*;
 use start_game::*;
+use submit_actions::*;
+use timeout_action::*;
+use update_config::*;
+use update_table_access::*;
+
+use state::{AccessList, AccessMode, ActionRecord, ShowChoice};
 
 declare_id!("7EZ1zWNMjuHh62dikk9TAo478VMzAiLkvg8S7Vm85T7s");
 
@@ -32,32 +120,105 @@ declare_id!("7EZ1zWNMjuHh62dikk9TAo478VMzAiLkvg8S7Vm85T7s");
 pub mod solana_poker {
     use super::*;
 
-    /// Creates a new poker table
+    /// Upgrade authority creates the protocol config singleton
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        treasury: Pubkey,
+        fee_bps: u16,
+        fee_cap: u64,
+    ) -> Result<()> {
+        initialize_config::handler(ctx, treasury, fee_bps, fee_cap)
+    }
+
+    /// Protocol admin updates admin, treasury and fee settings
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        new_admin: Pubkey,
+        treasury: Pubkey,
+        fee_bps: u16,
+        fee_cap: u64,
+    ) -> Result<()> {
+        update_config::handler(ctx, new_admin, treasury, fee_bps, fee_cap)
+    }
+
+    /// Protocol admin pauses or unpauses new tables, seats and games
+    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+        set_paused::handler(ctx, paused)
+    }
+
+    /// Creates an empty lobby page for listing tables
+    pub fn init_lobby_page(ctx: Context<InitLobbyPage>, page_index: u32) -> Result<()> {
+        init_lobby_page::handler(ctx, page_index)
+    }
+
+    /// Creates a new poker table and lists it in the lobby
     pub fn create_table(
         ctx: Context<CreateTable>,
         table_id: u64,
-        max_players: u8,
-        buy_in_min: u64,
-        buy_in_max: u64,
-        small_blind: u64,
-        backend_account: Pubkey,
+        params: CreateTableParams,
     ) -> Result<()> {
-        create_table::handler(
-            ctx,
-            table_id,
-            max_players,
-            buy_in_min,
-            buy_in_max,
-            small_blind,
-            backend_account,
-        )
+        create_table::handler(ctx, table_id, params)
+    }
+
+    /// Creator adds or removes a wallet on the table's allowlist or bans
+    pub fn update_table_access(
+        ctx: Context<UpdateTableAccess>,
+        wallet: Pubkey,
+        list: AccessList,
+        add: bool,
+    ) -> Result<()> {
+        update_table_access::handler(ctx, wallet, list, add)
+    }
+
+    /// Creator changes the table's access mode
+    pub fn set_access_mode(ctx: Context<SetAccessMode>, access_mode: AccessMode) -> Result<()> {
+        set_access_mode::handler(ctx, access_mode)
+    }
+
+    /// Creator proposes a new backend authority for the table
+    pub fn propose_backend(ctx: Context<ProposeBackend>, new_backend: Pubkey) -> Result<()> {
+        propose_backend::handler(ctx, new_backend)
+    }
+
+    /// Pending backend accepts and becomes the table authority
+    pub fn accept_backend(ctx: Context<AcceptBackend>) -> Result<()> {
+        accept_backend::handler(ctx)
+    }
+
+    /// Creator closes an empty table, draining the vault to a recipient
+    pub fn close_table(ctx: Context<CloseTable>) -> Result<()> {
+        close_table::handler(ctx)
     }
 
     /// Player joins a table with a buy-in
+    ///
+    /// On invite tables, wallets that are not allowlisted carry the
+    /// creator's signed invite as an Ed25519 instruction.
     pub fn join_table(ctx: Context<JoinTable>, buy_in: u64) -> Result<()> {
         join_table::handler(ctx, buy_in)
     }
 
+    /// Player cashes out their chips and leaves the table
+    pub fn leave_table(ctx: Context<LeaveTable>) -> Result<()> {
+        leave_table::handler(ctx)
+    }
+
+    /// Player cashes out a confidential stack and leaves the table
+    ///
+    /// Pass the attested decryption of the seat's encrypted stack, with its
+    /// Ed25519 instructions earlier in the transaction.
+    pub fn leave_table_confidential(
+        ctx: Context<LeaveTableConfidential>,
+        stack: u128,
+    ) -> Result<()> {
+        leave_table_confidential::handler(ctx, stack)
+    }
+
+    /// Player tops up or rebuys their stack between hands
+    pub fn add_chips(ctx: Context<AddChips>, amount: u64) -> Result<()> {
+        add_chips::handler(ctx, amount)
+    }
+
     /// Backend refunds all players and clears table
     pub fn refund_all<'info>(
         ctx: Context<'_, '_, 'info, 'info, RefundAll<'info>>,
@@ -65,59 +226,109 @@ pub mod solana_poker {
         refund_all::handler(ctx)
     }
 
+    /// Abandon a confidential hand before its result is proposed
+    ///
+    /// Stacks are left as they were. Backend only, or anyone once the hand
+    /// has been stuck for STUCK_HAND_SLOTS.
+    pub fn refund_hand_confidential<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RefundHandConfidential<'info>>,
+    ) -> Result<()> {
+        refund_hand_confidential::handler(ctx)
+    }
+
+    /// Player sits out or sits back in from the next hand
+    pub fn set_sitting_out(ctx: Context<SetSittingOut>, sitting_out: bool) -> Result<()> {
+        set_sitting_out::handler(ctx, sitting_out)
+    }
+
+    /// Player opts in to (or out of) being timed on-chain on their turns
+    pub fn set_action_clock(ctx: Context<SetActionClock>, opt_in: bool) -> Result<()> {
+        set_action_clock::handler(ctx, opt_in)
+    }
+
+    /// Player opts in to straddling the next hand from the straddle position
+    pub fn set_straddle(ctx: Context<SetStraddle>, straddle: bool) -> Result<()> {
+        set_straddle::handler(ctx, straddle)
+    }
+
     /// Admin starts a new game with blind bets
     ///
-    /// Pass small_blind and big_blind seat accounts via remaining_accounts
-    /// to collect blind bets at game start.
+    /// Pass every seat at the table via remaining_accounts, ordered from the
+    /// small blind position. The first two dealt-in seats post the blinds,
+    /// alongside any antes and straddle the table configures.
     pub fn start_game<'info>(
         ctx: Context<'_, '_, 'info, 'info, StartGame<'info>>,
         game_id: u64,
-        backend_account: Pubkey,
         small_blind_amount: u64,
         big_blind_amount: u64,
     ) -> Result<()> {
         start_game::handler(
             ctx,
             game_id,
-            backend_account,
             small_blind_amount,
             big_blind_amount,
         )
     }
 
-    /// Process cards in mini-batches (2 cards per batch, 8 batches total)
+    /// Process cards in mini-batches (2 cards per batch, in order)
+    ///
+    /// Cards are the hole cards of every dealt-in seat (2 each, 4 in Omaha)
+    /// followed by 5 community cards, so there are
+    /// ceil((hole cards * player_count + 5) / 2) batches.
     ///
-    /// Batch 0: Uses blockhash for shuffle seed and offset
-    /// Batch 1-6: Process cards 2-13
-    /// Batch 7: Process card 14, sets cards_processed = true, stage = Playing
+    /// Each batch draws its cards on-chain with e_rand and flags (encrypted)
+    /// any repeat of an earlier card; confirm_cards_batch then accepts the
+    /// batch or sends the repeated cards back for a redraw.
     ///
-    /// After batch 7, backend can proceed with off-chain gameplay.
+    /// Batch 0 also uses the slot to shuffle which seat gets which hand.
     pub fn process_cards_batch<'info>(
         ctx: Context<'_, '_, '_, 'info, ProcessCardsBatch<'info>>,
         batch_index: u8,
-        card_0: Vec<u8>,
-        card_1: Vec<u8>,
-        input_type: u8,
     ) -> Result<()> {
-        process_cards::handler(ctx, batch_index, card_0, card_1, input_type)
+        process_cards::handler(ctx, batch_index)
     }
 
-    /// Player reveals their hand (grants decrypt access to themselves)
-    pub fn reveal_hand<'info>(ctx: Context<'_, '_, '_, 'info, RevealHand<'info>>) -> Result<()> {
-        reveal_hand::handler(ctx)
+    /// Backend gets decrypt access to the drawn batch's duplicate flag
+    pub fn reveal_duplicate_flag<'info>(
+        ctx: Context<'_, '_, '_, 'info, RevealDuplicateFlag<'info>>,
+    ) -> Result<()> {
+        reveal_duplicate_flag::handler(ctx)
     }
 
-    /// Admin allows a player to decrypt the card_offset handle
-    /// Used to verify the offset value (0-51)
-    pub fn reveal_card_offset<'info>(
-        ctx: Context<'_, '_, '_, 'info, RevealCardOffset<'info>>,
+    /// Backend confirms a drawn batch with an attested decryption of its
+    /// duplicate flag
+    ///
+    /// After the last batch, backend can proceed with off-chain gameplay.
+    pub fn confirm_cards_batch<'info>(
+        ctx: Context<'_, '_, '_, 'info, ConfirmCardsBatch<'info>>,
+        duplicate_flag: u128,
     ) -> Result<()> {
-        reveal_card_offset::handler(ctx)
+        confirm_cards_batch::handler(ctx, duplicate_flag)
+    }
+
+    /// Record the all-in players' agreement to run the board twice
+    ///
+    /// Only once the hand's transcript shows the live seats all-in. Every
+    /// live player's signature over agree_run_it_twice::agreement_digest is
+    /// checked through Ed25519 precompile instructions. The second board is
+    /// then dealt through process_cards_batch and confirm_cards_batch.
+    pub fn agree_run_it_twice(ctx: Context<AgreeRunItTwice>, revealed: u8) -> Result<()> {
+        agree_run_it_twice::handler(ctx, revealed)
+    }
+
+    /// Player reveals their hand (grants decrypt access to themselves)
+    ///
+    /// Signed by the player; the program PDA owning the handles allows
+    /// exactly that seat's hole cards (two, or four in Omaha), without the
+    /// backend. Pass one allowance account per hole card.
+    pub fn reveal_hand<'info>(ctx: Context<'_, '_, '_, 'info, RevealHand<'info>>) -> Result<()> {
+        reveal_hand::handler(ctx)
     }
 
     /// Backend reveals all 5 community cards for off-chain gameplay
     ///
-    /// Pass 5 allowance accounts via remaining_accounts (one per community card).
+    /// Pass 5 allowance accounts via remaining_accounts (one per community card),
+    /// plus one per redrawn card when the board runs twice.
     /// Backend can then decrypt and reveal cards progressively during gameplay.
     pub fn reveal_community<'info>(
         ctx: Context<'_, '_, '_, 'info, RevealCommunity<'info>>,
@@ -125,15 +336,240 @@ pub mod solana_poker {
         reveal_community::handler(ctx)
     }
 
-    /// Settle the game and pay the winner
+    /// Open a reached street's community cards to spectators
+    ///
+    /// Allows the public SPECTATOR_VIEWER key on the board cards up to
+    /// `board_cards` (3, 4 or 5). Anyone may pass the hand's transcript as
+    /// proof; without one, the backend may open the board once the proposed
+    /// settlement shows a showdown. Pass one allowance account per newly
+    /// opened card via remaining_accounts.
+    pub fn reveal_street<'info>(
+        ctx: Context<'_, '_, '_, 'info, RevealStreet<'info>>,
+        board_cards: u8,
+    ) -> Result<()> {
+        reveal_street::handler(ctx, board_cards)
+    }
+
+    /// Open a live seat's hole cards to the table at showdown
+    ///
+    /// Valid while the proposed settlement is in its dispute window. Seats
+    /// that mucked stay hidden; a seat that chose to show one card opens
+    /// only that card.
+    /// Pass [viewer seat, viewer wallet, one allowance per opened card] per
+    /// viewer via remaining_accounts.
+    pub fn reveal_showdown<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RevealShowdown<'info>>,
+    ) -> Result<()> {
+        reveal_showdown::handler(ctx)
+    }
+
+    /// Player shows one or all hole cards to the table, or mucks them
+    ///
+    /// Usable after the player's fold is replayed or once the hand ends.
+    /// When showing, pass [viewer seat, viewer wallet, one allowance per
+    /// shown card] per viewer via remaining_accounts.
+    pub fn set_show_choice<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SetShowChoice<'info>>,
+        choice: ShowChoice,
+    ) -> Result<()> {
+        set_show_choice::handler(ctx, choice)
+    }
+
+    /// Player grants themselves decrypt access to their encrypted stack
+    ///
+    /// Pass 1 allowance account via remaining_accounts.
+    pub fn reveal_stack<'info>(ctx: Context<'_, '_, '_, 'info, RevealStack<'info>>) -> Result<()> {
+        reveal_stack::handler(ctx)
+    }
+
+    /// Propose the hand result and open the dispute window
     ///
     /// Called by backend after off-chain gameplay completes.
-    /// Transfers final_pot from vault to winner's wallet.
-    pub fn settle_game(
-        ctx: Context<SettleGame>,
-        winner_seat_index: u8,
-        final_pot: u64,
+    /// Pass every dealt-in seat via remaining_accounts with its total bet
+    /// and payout for the hand, plus the part won on the second board when
+    /// the board ran twice; folded_mask marks the seats that folded.
+    pub fn settle_game<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SettleGame<'info>>,
+        total_bets: Vec<u64>,
+        payouts: Vec<u64>,
+        second_payouts: Vec<u64>,
+        folded_mask: u16,
+    ) -> Result<()> {
+        settle_game::handler(ctx, total_bets, payouts, second_payouts, folded_mask)
+    }
+
+    /// Settle the game immediately when every dealt-in player signed the result
+    ///
+    /// Signatures over settle_game_signed::result_digest are checked through
+    /// Ed25519 precompile instructions earlier in the transaction.
+    pub fn settle_game_signed<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SettleGameSigned<'info>>,
+        total_bets: Vec<u64>,
+        payouts: Vec<u64>,
+        second_payouts: Vec<u64>,
+        folded_mask: u16,
+    ) -> Result<()> {
+        settle_game_signed::handler(ctx, total_bets, payouts, second_payouts, folded_mask)
+    }
+
+    /// Propose a confidential table's hand result on encrypted stacks
+    ///
+    /// Pass every dealt-in seat via remaining_accounts with client-encrypted
+    /// total bets and payouts; an unbalanced result voids the hand. The
+    /// result waits out the same dispute window as settle_game.
+    pub fn settle_game_confidential<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SettleGameConfidential<'info>>,
+        total_bets: Vec<Vec<u8>>,
+        payouts: Vec<Vec<u8>>,
+        folded_mask: u16,
+        input_type: u8,
+    ) -> Result<()> {
+        settle_game_confidential::handler(ctx, total_bets, payouts, folded_mask, input_type)
+    }
+
+    /// Give a player who could challenge a paid seat decrypt access to that
+    /// seat's hole cards and the board
+    pub fn open_challenge_evidence<'info>(
+        ctx: Context<'_, '_, '_, 'info, OpenChallengeEvidence<'info>>,
+    ) -> Result<()> {
+        open_challenge_evidence::handler(ctx)
+    }
+
+    /// Challenge a proposed settlement with attested card decryptions
+    ///
+    /// A successful challenge moves the accused's winnings on that board to
+    /// the challenger and reopens the dispute window. Hole cards are given
+    /// in deal order, two per seat or four in Omaha.
+    pub fn challenge_settlement<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ChallengeSettlement<'info>>,
+        accused_seat: u8,
+        board: [u128; 5],
+        challenger_cards: Vec<u128>,
+        accused_cards: Vec<u128>,
+        second_board: Option<[u128; 5]>,
+    ) -> Result<()> {
+        challenge_settlement::handler(
+            ctx,
+            accused_seat,
+            board,
+            challenger_cards,
+            accused_cards,
+            second_board,
+        )
+    }
+
+    /// Apply an unchallenged settlement once the dispute window closes
+    ///
+    /// Permissionless. Credits winnings to seat stacks; lamports stay in
+    /// the vault until leave_table.
+    pub fn finalize_settlement<'info>(
+        ctx: Context<'_, '_, 'info, 'info, FinalizeSettlement<'info>>,
+    ) -> Result<()> {
+        finalize_settlement::handler(ctx)
+    }
+
+    /// Challenge a confidential table's proposed result with attested card
+    /// decryptions
+    ///
+    /// Moves the accused's payout to the challenger when the accused was
+    /// paid and the challenger covered their bets, and reopens the dispute
+    /// window.
+    pub fn challenge_settlement_confidential<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ChallengeSettlementConfidential<'info>>,
+        accused_seat: u8,
+        board: [u128; 5],
+        challenger_cards: Vec<u128>,
+        accused_cards: Vec<u128>,
+        second_board: Option<[u128; 5]>,
     ) -> Result<()> {
-        settle_game::handler(ctx, winner_seat_index, final_pot)
+        challenge_settlement_confidential::handler(
+            ctx,
+            accused_seat,
+            board,
+            challenger_cards,
+            accused_cards,
+            second_board,
+        )
+    }
+
+    /// Apply an unchallenged confidential settlement once the dispute window
+    /// closes
+    ///
+    /// Permissionless. Updates the encrypted stacks; lamports stay in the
+    /// vault until leave_table_confidential.
+    pub fn finalize_settlement_confidential<'info>(
+        ctx: Context<'_, '_, 'info, 'info, FinalizeSettlementConfidential<'info>>,
+    ) -> Result<()> {
+        finalize_settlement_confidential::handler(ctx)
+    }
+
+    /// Open an on-chain replay of a hand's signed action transcript
+    pub fn open_transcript<'info>(
+        ctx: Context<'_, '_, 'info, 'info, OpenTranscript<'info>>,
+    ) -> Result<()> {
+        open_transcript::handler(ctx)
+    }
+
+    /// Replay the next player-signed actions of a hand
+    pub fn submit_actions(ctx: Context<SubmitActions>, actions: Vec<ActionRecord>) -> Result<()> {
+        submit_actions::handler(ctx, actions)
+    }
+
+    /// Backend starts the on-chain action clock for every seat of a hand
+    pub fn arm_action_clock(ctx: Context<ArmActionClock>) -> Result<()> {
+        arm_action_clock::handler(ctx)
+    }
+
+    /// Check or fold for a player whose action deadline (and time bank)
+    /// ran out
+    ///
+    /// Permissionless; the player's seat is passed as player_seat. Only
+    /// once the backend armed the clock or the player opted in.
+    pub fn timeout_action(ctx: Context<TimeoutAction>) -> Result<()> {
+        timeout_action::handler(ctx)
+    }
+
+    /// Prove a seat signed two different actions for the same turn
+    ///
+    /// Blocks adjudication on the transcript and puts back a result it
+    /// already overruled.
+    pub fn prove_equivocation(
+        ctx: Context<ProveEquivocation>,
+        sequence: u16,
+        prev: [u8; 32],
+        first: ActionRecord,
+        second: ActionRecord,
+    ) -> Result<()> {
+        prove_equivocation::handler(ctx, sequence, prev, first, second)
+    }
+
+    /// Rewrite a pending settlement that contradicts the replayed transcript
+    pub fn adjudicate_transcript(ctx: Context<AdjudicateTranscript>) -> Result<()> {
+        adjudicate_transcript::handler(ctx)
+    }
+
+    /// Close a transcript after its game is closed
+    pub fn close_transcript(ctx: Context<CloseTranscript>) -> Result<()> {
+        close_transcript::handler(ctx)
+    }
+
+    /// Upgrade a table account to the current layout
+    pub fn migrate_table(ctx: Context<MigrateTable>) -> Result<()> {
+        migrate_table::handler(ctx)
+    }
+
+    /// Create the access lists of a table that predates them
+    pub fn init_table_access(ctx: Context<InitTableAccess>) -> Result<()> {
+        init_table_access::handler(ctx)
+    }
+
+    /// Upgrade a game account to the current layout
+    pub fn migrate_game(ctx: Context<MigrateGame>) -> Result<()> {
+        migrate_game::handler(ctx)
+    }
+
+    /// Upgrade a player seat account to the current layout
+    pub fn migrate_seat(ctx: Context<MigrateSeat>) -> Result<()> {
+        migrate_seat::handler(ctx)
     }
 }
@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+use crate::state::migration::{stored_version, write_migrated, LEGACY_VERSION};
+use crate::state::poker_game::PokerGameV0;
+use crate::state::{PokerGame, PokerTable};
+use crate::error::PokerError;
+
+/// Upgrades a game account to the current layout in place
+///
+/// Per-seat vectors are sized from the table's max_players, so the
+/// (already migrated) parent table is passed along.
+pub fn handler(ctx: Context<MigrateGame>) -> Result<()> {
+    let info = ctx.accounts.game.to_account_info();
+    let table = &ctx.accounts.table;
+
+    let game: PokerGame = {
+        let data = info.try_borrow_data()?;
+        require!(
+            data.starts_with(PokerGame::DISCRIMINATOR),
+            anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+        );
+
+        match stored_version(&data, PokerGame::LEGACY_LEN) {
+            PokerGame::VERSION => {
+                msg!("Game {} already at version {}", info.key(), PokerGame::VERSION);
+                return Ok(());
+            }
+            LEGACY_VERSION => PokerGameV0::deserialize(&mut &data[8..])
+                .map_err(|_| anchor_lang::error::ErrorCode::AccountDidNotDeserialize)?
+                .into_current(table.max_players),
+            _ => return err!(PokerError::UnsupportedAccountVersion),
+        }
+    };
+
+    require!(game.table == table.key(), PokerError::NoActiveGame);
+
+    write_migrated(
+        &info,
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        PokerGame::space(table.max_players, game.variant),
+        &game,
+    )?;
+
+    msg!("Game {} migrated to version {}", info.key(), PokerGame::VERSION);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateGame<'info> {
+    #[account(
+        constraint = table.version == PokerTable::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub table: Account<'info, PokerTable>,
+
+    /// CHECK: Deserialized by the handler, which may not match the current layout
+    #[account(mut, owner = crate::ID)]
+    pub game: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+use crate::state::migration::{stored_version, write_migrated, LEGACY_VERSION};
+use crate::state::player_seat::PlayerSeatV0;
+use crate::state::PlayerSeat;
+use crate::error::PokerError;
+
+/// Upgrades a seat account to the current layout in place
+///
+/// Permissionless: the upgrade is deterministic, the payer only covers any
+/// extra rent. Already-current seats are left untouched.
+pub fn handler(ctx: Context<MigrateSeat>) -> Result<()> {
+    let info = ctx.accounts.seat.to_account_info();
+
+    let seat: PlayerSeat = {
+        let data = info.try_borrow_data()?;
+        require!(
+            data.starts_with(PlayerSeat::DISCRIMINATOR),
+            anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+        );
+
+        match stored_version(&data, PlayerSeat::LEGACY_LEN) {
+            PlayerSeat::VERSION => {
+                msg!("Seat {} already at version {}", info.key(), PlayerSeat::VERSION);
+                return Ok(());
+            }
+            LEGACY_VERSION => PlayerSeatV0::deserialize(&mut &data[8..])
+                .map_err(|_| anchor_lang::error::ErrorCode::AccountDidNotDeserialize)?
+                .into(),
+            _ => return err!(PokerError::UnsupportedAccountVersion),
+        }
+    };
+
+    write_migrated(
+        &info,
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        PlayerSeat::LEN,
+        &seat,
+    )?;
+
+    msg!("Seat {} migrated to version {}", info.key(), PlayerSeat::VERSION);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateSeat<'info> {
+    /// CHECK: Deserialized by the handler, which may not match the current layout
+    #[account(mut, owner = crate::ID)]
+    pub seat: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
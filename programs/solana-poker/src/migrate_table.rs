@@ -0,0 +1,74 @@
+use anchor_lang::prelude::*;
+use crate::state::migration::{stored_version, write_migrated, LEGACY_VERSION};
+use crate::state::poker_table::PokerTableV0;
+use crate::state::{LobbyPage, PokerTable};
+use crate::error::PokerError;
+
+/// Upgrades a table account to the current layout in place
+///
+/// Permissionless: the upgrade is deterministic, the payer only covers any
+/// extra rent. Already-current tables are left untouched.
+///
+/// Unversioned tables predate the lobby, so they are listed on the
+/// lobby_page passed in.
+pub fn handler(ctx: Context<MigrateTable>) -> Result<()> {
+    let info = ctx.accounts.table.to_account_info();
+
+    let mut table: PokerTable = {
+        let data = info.try_borrow_data()?;
+        require!(
+            data.starts_with(PokerTable::DISCRIMINATOR),
+            anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+        );
+
+        match stored_version(&data, PokerTable::LEGACY_LEN) {
+            PokerTable::VERSION => {
+                msg!("Table {} already at version {}", info.key(), PokerTable::VERSION);
+                return Ok(());
+            }
+            LEGACY_VERSION => PokerTableV0::deserialize(&mut &data[8..])
+                .map_err(|_| anchor_lang::error::ErrorCode::AccountDidNotDeserialize)?
+                .into(),
+            _ => return err!(PokerError::UnsupportedAccountVersion),
+        }
+    };
+
+    let lobby_page = ctx
+        .accounts
+        .lobby_page
+        .as_mut()
+        .ok_or(PokerError::LobbyPageRequired)?;
+    table.lobby_page = lobby_page.page_index;
+    lobby_page.insert(table.lobby_entry(info.key()))?;
+
+    write_migrated(
+        &info,
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        PokerTable::LEN,
+        &table,
+    )?;
+
+    msg!("Table {} migrated to version {}", info.key(), PokerTable::VERSION);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateTable<'info> {
+    /// CHECK: Deserialized by the handler, which may not match the current layout
+    #[account(mut, owner = crate::ID)]
+    pub table: UncheckedAccount<'info>,
+
+    /// Page to list the table on; required unless it is already current
+    #[account(
+        mut,
+        seeds = [b"lobby", lobby_page.page_index.to_le_bytes().as_ref()],
+        bump = lobby_page.bump
+    )]
+    pub lobby_page: Option<Account<'info, LobbyPage>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
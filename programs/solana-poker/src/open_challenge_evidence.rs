@@ -0,0 +1,178 @@
+use crate::constants::INCO_AUTHORITY_SEED;
+use crate::error::PokerError;
+use crate::inco_authority::top_up;
+use crate::state::{
+    ConfidentialSettlement, GameStage, PlayerSeat, PokerGame, PokerTable, Settlement,
+};
+use anchor_lang::prelude::*;
+use inco_lightning::cpi::accounts::Allow;
+use inco_lightning::cpi::allow;
+use inco_lightning::program::IncoLightning;
+
+/// Let a would-be challenger decrypt the evidence for challenge_settlement
+///
+/// Allows the player the accused seat's hole cards and the board (plus the
+/// second board's redrawn cards when the board ran twice), so they can
+/// request the attested decryptions a challenge needs.
+///
+/// Only open during the dispute window and only to a player who could
+/// challenge the accused: both seats live, the accused paid, and the
+/// challenger in for at least as much. A paid seat that mucked is opened
+/// all the same; its payout is what is being disputed. On confidential
+/// tables, where payouts stay encrypted, both seats being live is enough.
+///
+/// Pass the Settlement of a public table or the ConfidentialSettlement of a
+/// confidential one, not both.
+///
+/// Requires one allowance account in remaining_accounts per handle: the
+/// accused's hole cards, then the board in order, then any second board
+/// cards.
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, OpenChallengeEvidence<'info>>) -> Result<()> {
+    let game = &ctx.accounts.game;
+    let challenger = &ctx.accounts.challenger_seat;
+    let accused = &ctx.accounts.accused_seat;
+
+    require!(
+        challenger.game == game.key() && accused.game == game.key(),
+        PokerError::SeatNotInGame
+    );
+
+    let (c, a) = (challenger.seat_index as usize, accused.seat_index as usize);
+    let (deadline_slot, eligible) = match (
+        &ctx.accounts.settlement,
+        &ctx.accounts.confidential_settlement,
+    ) {
+        (Some(settlement), None) => (
+            settlement.deadline_slot,
+            settlement.is_live(challenger.seat_index)
+                && settlement.is_live(accused.seat_index)
+                && settlement.payouts[a] > 0
+                && settlement.total_bets[c] >= settlement.total_bets[a],
+        ),
+        // Confidential payouts stay encrypted; challenge_settlement_confidential
+        // checks them, so any live seat may see another live hand
+        (None, Some(settlement)) => (
+            settlement.deadline_slot,
+            settlement.is_live(challenger.seat_index) && settlement.is_live(accused.seat_index),
+        ),
+        _ => return err!(PokerError::InvalidChallenge),
+    };
+
+    let clock = Clock::get()?;
+    require!(
+        clock.slot <= deadline_slot,
+        PokerError::DisputeWindowClosed
+    );
+    require!(
+        challenger.seat_index != accused.seat_index && eligible,
+        PokerError::InvalidChallenge
+    );
+
+    let mut handles = game
+        .get_player_hole_cards(accused.seat_index)
+        .ok_or(PokerError::SeatNotInGame)?
+        .to_vec();
+    handles.extend_from_slice(&game.community_cards);
+    if game.run_twice {
+        handles.extend_from_slice(&game.second_board[game.second_board_from as usize..]);
+    }
+    require!(
+        ctx.remaining_accounts.len() >= handles.len(),
+        PokerError::MissingAllowanceAccounts
+    );
+
+    let cpi_program = ctx.accounts.inco_lightning_program.to_account_info();
+    let authority = ctx.accounts.inco_authority.to_account_info();
+    let player = ctx.accounts.player.to_account_info();
+    let system_program = ctx.accounts.system_program.to_account_info();
+    let seeds = &[INCO_AUTHORITY_SEED, &[ctx.bumps.inco_authority]];
+    let signer = &[&seeds[..]];
+
+    top_up(&authority, &player, &system_program)?;
+
+    for (handle, allowance) in handles.iter().zip(ctx.remaining_accounts.iter()) {
+        let cpi_ctx = CpiContext::new_with_signer(
+            cpi_program.clone(),
+            Allow {
+                allowance_account: allowance.clone(),
+                signer: authority.clone(),
+                allowed_address: player.clone(),
+                system_program: system_program.clone(),
+            },
+            signer,
+        );
+        allow(cpi_ctx, handle.0, true, player.key())?;
+    }
+
+    top_up(&authority, &player, &system_program)?;
+
+    msg!(
+        "Game {} evidence against seat {} opened to seat {}",
+        game.game_id,
+        accused.seat_index,
+        challenger.seat_index
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct OpenChallengeEvidence<'info> {
+    #[account(
+        constraint = table.version == PokerTable::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub table: Account<'info, PokerTable>,
+
+    #[account(
+        constraint = game.table == table.key() @ PokerError::NoActiveGame,
+        constraint = game.stage == GameStage::PendingSettlement @ PokerError::InvalidGameStage,
+        constraint = game.version == PokerGame::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub game: Account<'info, PokerGame>,
+
+    #[account(
+        seeds = [b"settlement", game.key().as_ref()],
+        bump = settlement.bump,
+        constraint = settlement.version == Settlement::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub settlement: Option<Account<'info, Settlement>>,
+
+    #[account(
+        seeds = [b"confidential_settlement", game.key().as_ref()],
+        bump = confidential_settlement.bump,
+        constraint = confidential_settlement.version == ConfidentialSettlement::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub confidential_settlement: Option<Account<'info, ConfidentialSettlement>>,
+
+    /// Seat of the player preparing the challenge
+    #[account(
+        seeds = [b"player_seat", table.key().as_ref(), player.key().as_ref()],
+        bump = challenger_seat.bump,
+        constraint = challenger_seat.version == PlayerSeat::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub challenger_seat: Account<'info, PlayerSeat>,
+
+    /// Paid seat whose hand is disputed
+    #[account(
+        seeds = [b"player_seat", table.key().as_ref(), accused_seat.player.as_ref()],
+        bump = accused_seat.bump,
+        constraint = accused_seat.version == PlayerSeat::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub accused_seat: Account<'info, PlayerSeat>,
+
+    /// Challenging player; receives decrypt access and funds the allowance accounts
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    /// CHECK: Program PDA that owns the card handles
+    #[account(
+        mut,
+        seeds = [INCO_AUTHORITY_SEED],
+        bump
+    )]
+    pub inco_authority: AccountInfo<'info>,
+
+    pub inco_lightning_program: Program<'info, IncoLightning>,
+
+    pub system_program: Program<'info, System>,
+}
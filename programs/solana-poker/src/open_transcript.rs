@@ -0,0 +1,111 @@
+use crate::error::PokerError;
+use crate::state::{GameStage, PlayerSeat, PokerGame, PokerTable, Transcript};
+use anchor_lang::prelude::*;
+
+/// Open an on-chain replay of a hand's signed action transcript
+///
+/// Permissionless. Pass every dealt-in seat via remaining_accounts; their
+/// stacks, posted forced bets and the positions start_game recorded are
+/// the starting point of the replay, and Omaha hands replay under pot
+/// limit. Opening records the seat to act, whose clock only runs once the
+/// backend or that player started it (see timeout_action). Works
+/// while the hand is playing or its settlement is pending, since neither
+/// touches seat stacks.
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, OpenTranscript<'info>>) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let transcript = &mut ctx.accounts.transcript;
+
+    require!(
+        ctx.remaining_accounts.len() == game.player_count as usize,
+        PokerError::InvalidSeatAccounts
+    );
+
+    let table_key = ctx.accounts.table.key();
+    let mut seen_mask: u16 = 0;
+    let mut seats = Vec::with_capacity(ctx.remaining_accounts.len());
+
+    for seat_info in ctx.remaining_accounts.iter() {
+        let seat: Account<PlayerSeat> = Account::try_from(seat_info)?;
+        require!(seat.version == PlayerSeat::VERSION, PokerError::UnsupportedAccountVersion);
+
+        let expected = Pubkey::create_program_address(
+            &[
+                b"player_seat",
+                table_key.as_ref(),
+                seat.player.as_ref(),
+                &[seat.bump],
+            ],
+            ctx.program_id,
+        )
+        .map_err(|_| PokerError::PlayerNotAtTable)?;
+        require!(expected == *seat_info.key, PokerError::PlayerNotAtTable);
+        require!(seat.game == game.key(), PokerError::SeatNotInGame);
+
+        let seat_bit = 1u16 << seat.seat_index;
+        require!(seen_mask & seat_bit == 0, PokerError::InvalidSeatAccounts);
+        seen_mask |= seat_bit;
+
+        seats.push((
+            seat.seat_index,
+            seat.player,
+            seat.chips,
+            seat.total_bet,
+            seat.current_bet,
+        ));
+    }
+
+    transcript.version = Transcript::VERSION;
+    transcript.game = game.key();
+    transcript.head = Transcript::genesis_head(&game.key(), game.game_id);
+    transcript.payer = ctx.accounts.payer.key();
+    transcript.bump = ctx.bumps.transcript;
+    transcript.pot_limit = game.variant.is_pot_limit();
+    transcript.start(
+        &seats,
+        game.small_blind_seat,
+        game.big_blind_seat,
+        game.big_blind,
+        game.first_to_act,
+    )?;
+    game.set_turn(transcript.next_to_act(), Clock::get()?.slot);
+
+    msg!(
+        "Transcript opened for game {}. Seat {} to act",
+        game.game_id,
+        transcript.to_act
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct OpenTranscript<'info> {
+    #[account(
+        constraint = !table.confidential @ PokerError::ConfidentialTable,
+        constraint = table.version == PokerTable::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub table: Account<'info, PokerTable>,
+
+    #[account(
+        mut,
+        constraint = game.table == table.key() @ PokerError::NoActiveGame,
+        constraint = game.cards_processed @ PokerError::CardsNotProcessed,
+        constraint = matches!(game.stage, GameStage::Playing | GameStage::PendingSettlement) @ PokerError::InvalidGameStage,
+        constraint = game.version == PokerGame::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub game: Account<'info, PokerGame>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = Transcript::LEN,
+        seeds = [b"transcript", game.key().as_ref()],
+        bump
+    )]
+    pub transcript: Account<'info, Transcript>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -1,165 +1,184 @@
+use crate::constants::{COMMUNITY_CARDS, INCO_AUTHORITY_SEED};
 use crate::error::PokerError;
-use crate::state::{GameStage, PokerGame, PokerTable};
+use crate::inco_authority::top_up;
+use crate::state::{PokerGame, PokerTable};
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::clock::Clock;
 use inco_lightning::cpi::accounts::Operation;
-use inco_lightning::cpi::{self, e_add, new_euint128};
+use inco_lightning::cpi::{self, e_and, e_eq, e_or, e_rand, e_rem, e_select, e_shl};
 use inco_lightning::program::IncoLightning;
 use inco_lightning::types::Euint128;
 
-/// Process cards in mini-batches (2 cards per batch, 8 batches total)
+/// Draw cards in mini-batches (2 cards per batch, in order)
 ///
-/// NEW FLOW:
-/// - Batch 0: Uses blockhash for shuffle seed and offset (no e_rand)
-/// - Batch 1-6: Process cards 2-13
-/// - Batch 7: Process card 14, set cards_processed = true, stage = Playing
+/// Cards 0..kn are the k hole cards (2, or 4 in Omaha) of each of the n
+/// dealt-in seats, followed by the 5 community cards, so a heads-up
+/// hold'em hand needs 5 batches and a 10-handed one 13 (see
+/// PokerGame::batch_count). After a run-twice
+/// agreement, further batches draw the second board.
 ///
-/// Uses blockhash instead of e_rand to avoid oscillation issues:
-/// - shuffle_seed = slot hash (for Fisher-Yates shuffle)
-/// - card_offset = encrypted(slot % 52) (for card value offset)
+/// Every card is drawn on-chain as e_rand % 52 (% 36 on short-deck
+/// tables, see hand_eval::short_deck_card_index), owned by the program's
+/// Inco authority PDA, so no party (backend included) knows any plaintext
+/// and one revealed card says nothing about the others.
 ///
-/// After batch 7 completes, backend can proceed with off-chain gameplay.
+/// Independent draws can repeat a card, so each new card is checked
+/// against an encrypted bitmask of the cards dealt so far (the second
+/// board also avoids every card of the first), a fixed six operations
+/// per card however deep into the deck the batch is. The batch leaves an
+/// encrypted duplicate_flag that only the backend may decrypt (through
+/// reveal_duplicate_flag); it learns nothing but which of the batch's
+/// cards repeated, and confirm_cards_batch either accepts the batch or
+/// marks those cards in redraw_mask. The next call for the same batch then
+/// draws only the marked cards, so each card is retried on its own: even
+/// the last card of a full table (see GameVariant::max_players) lands on
+/// an unused card at least once in every four or five tries.
 pub fn handler<'info>(
     ctx: Context<'_, '_, '_, 'info, ProcessCardsBatch<'info>>,
     batch_index: u8,
-    card_0: Vec<u8>,
-    card_1: Vec<u8>,
-    input_type: u8,
 ) -> Result<()> {
     let game = &mut ctx.accounts.game;
 
     // ===== VALIDATION =====
+    require!(!game.cards_processed, PokerError::CardsAlreadyProcessed);
+    require!(game.is_dealing(), PokerError::InvalidGameStage);
+    require!(!game.batch_pending, PokerError::BatchPending);
+    let batch_count = game.batch_count();
     require!(
-        game.stage == GameStage::Waiting,
-        PokerError::InvalidGameStage
+        batch_index == game.next_batch && batch_index < batch_count,
+        PokerError::InvalidBatchIndex
     );
-    require!(!game.cards_processed, PokerError::CardsAlreadyProcessed);
-    require!(batch_index < 8, PokerError::InvalidBatchIndex);
 
+    // Handles are created by the program's Inco authority PDA, so only
+    // program instructions can grant decrypt access to them
     let cpi_program = ctx.accounts.inco_lightning_program.to_account_info();
-    let authority = ctx.accounts.backend.to_account_info();
+    let authority = ctx.accounts.inco_authority.to_account_info();
+    let backend = ctx.accounts.backend.to_account_info();
+    let system_program = ctx.accounts.system_program.to_account_info();
+    let seeds = &[INCO_AUTHORITY_SEED, &[ctx.bumps.inco_authority]];
+    let signer = &[&seeds[..]];
 
     let op_accounts = Operation {
         signer: authority.clone(),
     };
+    let op = || CpiContext::new_with_signer(cpi_program.clone(), op_accounts.clone(), signer);
+    top_up(&authority, &backend, &system_program)?;
 
-    // ===== BATCH 0: Generate shuffle seed and offset from blockhash =====
+    // ===== BATCH 0: Shuffle hand assignment from the slot =====
     if batch_index == 0 {
-        // Get current slot as source of randomness
-        let clock = Clock::get()?;
-        let slot = clock.slot;
-
-        // Use slot as shuffle seed (deterministic, based on when tx lands)
+        let slot = Clock::get()?.slot;
         game.shuffle_seed = slot;
-
-        // Compute offset: slot % 52 (value between 0-51)
-        let offset_value = (slot % 52) as u128;
-
-        // Encrypt the offset value using as_euint128
-        // This creates an encrypted handle for the offset
-        let encrypted_offset: Euint128 = cpi::as_euint128(
-            CpiContext::new(cpi_program.clone(), op_accounts.clone()),
-            offset_value,
-        )?;
-
-        game.card_offset = encrypted_offset;
-        game.shuffled_indices = do_simple_shuffle(slot);
+        let dealt_in = game.player_count as usize;
+        do_simple_shuffle(&mut game.shuffled_indices[..dealt_in], slot);
 
         msg!(
-            "Batch 0: slot={}, offset={}, shuffled_indices={:?}",
+            "Batch 0: slot={}, shuffled_indices={:?}",
             slot,
-            offset_value,
             game.shuffled_indices
         );
     }
 
-    // Use stored encrypted offset for all batches
-    let card_offset = game.card_offset;
-
-    let cards = [card_0, card_1];
-    let base_idx = (batch_index as usize) * 2;
-
-    // ===== PROCESS 2 CARDS =====
-    for i in 0..2 {
-        let actual_idx = base_idx + i;
+    let deck_size = cpi::as_euint128(op(), game.variant.deck_size() as u128)?;
+    let zero = cpi::as_euint128(op(), 0)?;
+    let one = cpi::as_euint128(op(), 1)?;
+
+    let hole_cards = game.total_cards() - COMMUNITY_CARDS as usize;
+    let per_seat = game.hole_cards_per_player();
+    let mut duplicate_flag = zero;
+
+    // Cards of confirmed batches (plus the kept cards of this one when
+    // redrawing); games migrated mid-deal rebuild the mask once from the
+    // cards already dealt
+    let positions = game.batch_positions(batch_index);
+    let redraw = game.redraw_mask;
+    let mut used = game.used_cards;
+    if redraw != 0 {
+        used = game.batch_used_cards;
+    } else if batch_index == 0 {
+        used = zero;
+    } else if !used.is_initialized() {
+        used = zero;
+        for earlier in 0..positions.start {
+            let bit = e_shl(op(), one, game.card_at(earlier), 0)?;
+            used = e_or(op(), used, bit, 0)?;
+        }
+    }
 
-        // Skip card 15+ (only 15 cards total)
-        if actual_idx >= 15 {
+    // ===== DRAW 2 CARDS (or the ones to redraw) =====
+    for (slot, position) in positions.enumerate() {
+        if redraw != 0 && redraw & (1 << slot) == 0 {
             continue;
         }
-
-        // Convert ciphertext to Eu128
-        let enc_bck_crd: Euint128 = new_euint128(
-            CpiContext::new(cpi_program.clone(), op_accounts.clone()),
-            cards[i].clone(),
-            input_type,
-        )?;
-
-        // Apply encrypted offset (0-51) to card value
-        // scalar_byte = 0 means both operands are ciphertexts
-        let enc_offset_crd: Euint128 = e_add(
-            CpiContext::new(cpi_program.clone(), op_accounts.clone()),
-            enc_bck_crd,
-            card_offset,
-            0,
-        )?;
-
-        // Store based on card type
-        if actual_idx < 10 {
-            // Hole cards: apply shuffle to player assignment
-            let pair_idx = actual_idx / 2;
-            let shuffled_pair = game.shuffled_indices[pair_idx % 5] as usize;
-            let card_slot = shuffled_pair * 2 + (actual_idx % 2);
-            game.deal_cards[card_slot] = enc_offset_crd;
-            msg!("Hole {} -> slot {}", actual_idx, card_slot);
+        let random: Euint128 = e_rand(op(), 0)?;
+        let card: Euint128 = e_rem(op(), random, deck_size, 0)?;
+
+        // Non-zero when the card's bit is already set; the flag marks the
+        // batch slot, never the card itself
+        let bit = e_shl(op(), one, card, 0)?;
+        let repeat = e_and(op(), used, bit, 0)?;
+        let fresh = e_eq(op(), repeat, zero, 0)?;
+        let slot_bit = cpi::as_euint128(op(), 1 << slot)?;
+        let flagged = e_select(op(), fresh, zero, slot_bit, 0)?;
+        duplicate_flag = e_or(op(), duplicate_flag, flagged, 0)?;
+        used = e_or(op(), used, bit, 0)?;
+
+        game.set_card(position, card);
+        if position < hole_cards {
+            // Hole cards: hand h belongs to seat shuffled_indices[h]
+            msg!(
+                "Hole {} -> seat {}",
+                position,
+                game.shuffled_indices[position / per_seat]
+            );
         } else {
-            // Community cards (10-14) - stored directly (no shuffle for community)
-            let comm_idx = actual_idx - 10;
-            game.community_cards[comm_idx] = enc_offset_crd;
-            msg!("Community {} -> slot {}", actual_idx, comm_idx);
+            msg!("Board card {} drawn", position - hole_cards);
         }
     }
 
-    // ===== FINALIZE AFTER BATCH 7 =====
-    if batch_index == 7 {
-        game.cards_processed = true;
-        game.stage = GameStage::Playing;
-        msg!("All cards processed! cards_processed=true, stage=Playing");
-        msg!("Backend can now proceed with off-chain gameplay");
-    } else {
-        msg!("Batch {} done", batch_index);
-    }
+    game.duplicate_flag = duplicate_flag;
+    game.batch_used_cards = used;
+    game.batch_pending = true;
+    top_up(&authority, &backend, &system_program)?;
+
+    msg!(
+        "Batch {} drawn (redraw mask {:#04b}), awaiting confirmation",
+        batch_index,
+        redraw
+    );
 
     Ok(())
 }
 
 /// Simple shuffle using slot as seed
 /// Deterministic based on the slot when batch 0 transaction lands
-fn do_simple_shuffle(slot: u64) -> [u8; 5] {
-    let mut indices: [u8; 5] = [0, 1, 2, 3, 4];
-    let seed_bytes = slot.to_le_bytes();
-
-    // Fisher-Yates shuffle using slot bytes as source of randomness
-    for i in (1..5).rev() {
-        let j = (seed_bytes[i % 8] as usize) % (i + 1);
+fn do_simple_shuffle(indices: &mut [u8], slot: u64) {
+    // xorshift64 stream seeded from the slot, so full-ring tables get a
+    // fresh draw per swap instead of reusing the 8 slot bytes
+    let mut state = slot ^ 0x9E37_79B9_7F4A_7C15;
+
+    // Fisher-Yates shuffle
+    for i in (1..indices.len()).rev() {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        let j = (state % (i as u64 + 1)) as usize;
         indices.swap(i, j);
     }
-
-    indices
 }
 
 #[derive(Accounts)]
 #[instruction(batch_index: u8)]
 pub struct ProcessCardsBatch<'info> {
     #[account(
-        constraint = table.backend == backend.key() @ PokerError::NotBackend
+        constraint = table.version == PokerTable::VERSION @ PokerError::UnsupportedAccountVersion
     )]
     pub table: Account<'info, PokerTable>,
 
     #[account(
         mut,
         constraint = game.table == table.key() @ PokerError::NoActiveGame,
-        constraint = game.stage == GameStage::Waiting @ PokerError::InvalidGameStage
+        constraint = game.is_dealing() @ PokerError::InvalidGameStage,
+        constraint = game.version == PokerGame::VERSION @ PokerError::UnsupportedAccountVersion
     )]
     pub game: Account<'info, PokerGame>,
 
@@ -169,6 +188,14 @@ pub struct ProcessCardsBatch<'info> {
     )]
     pub backend: Signer<'info>,
 
+    /// CHECK: Program PDA that owns the card handles
+    #[account(
+        mut,
+        seeds = [INCO_AUTHORITY_SEED],
+        bump
+    )]
+    pub inco_authority: AccountInfo<'info>,
+
     pub inco_lightning_program: Program<'info, IncoLightning>,
 
     pub system_program: Program<'info, System>,
@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+use crate::state::PokerTable;
+use crate::error::PokerError;
+
+/// Creator proposes a new backend authority for the table
+///
+/// The proposal only takes effect once the new backend calls accept_backend.
+/// Proposing again replaces any pending proposal.
+pub fn handler(ctx: Context<ProposeBackend>, new_backend: Pubkey) -> Result<()> {
+    let table = &mut ctx.accounts.table;
+    table.pending_backend = Some(new_backend);
+
+    msg!(
+        "Table {} backend rotation proposed: {} -> {}",
+        table.table_id,
+        table.backend,
+        new_backend
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ProposeBackend<'info> {
+    #[account(
+        mut,
+        constraint = table.creator == creator.key() @ PokerError::NotCreator,
+        constraint = table.version == PokerTable::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub table: Account<'info, PokerTable>,
+
+    pub creator: Signer<'info>,
+}
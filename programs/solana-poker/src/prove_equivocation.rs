@@ -0,0 +1,107 @@
+use crate::constants::DISPUTE_WINDOW_SLOTS;
+use crate::ed25519::signers_of;
+use crate::error::PokerError;
+use crate::state::{ActionRecord, GameStage, PokerGame, Settlement, Transcript};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+
+/// Prove a seat signed two different actions for the same turn
+///
+/// Permissionless. `first` and `second` must both be signed by the seat's
+/// player over Transcript::action_digest at the same `sequence` and
+/// previous head `prev`, carried as Ed25519 precompile instructions
+/// earlier in the transaction. An honest player signs one action per turn,
+/// so this is proof against that seat: it could have replayed whichever
+/// action suits it, and the transcript is no longer evidence against the
+/// settlement.
+///
+/// The seat is recorded in Transcript::equivocated_mask, which blocks
+/// adjudicate_transcript. When the settlement was already adjudicated, the
+/// overruled result is put back and the dispute window reopens.
+pub fn handler(
+    ctx: Context<ProveEquivocation>,
+    sequence: u16,
+    prev: [u8; 32],
+    first: ActionRecord,
+    second: ActionRecord,
+) -> Result<()> {
+    let game = &ctx.accounts.game;
+    let transcript = &mut ctx.accounts.transcript;
+    let clock = Clock::get()?;
+
+    require!(
+        first.seat_index == second.seat_index
+            && first != second
+            && (first.seat_index as usize) < transcript.players.len(),
+        PokerError::NotAnEquivocation
+    );
+    let player = transcript.players[first.seat_index as usize];
+    for action in [&first, &second] {
+        let digest = Transcript::action_digest(&game.key(), game.game_id, sequence, &prev, action);
+        let signers = signers_of(&ctx.accounts.instructions, &digest)?;
+        require!(signers.contains(&player), PokerError::MissingPlayerSignature);
+    }
+
+    transcript.equivocated_mask |= 1 << first.seat_index;
+
+    match ctx.accounts.settlement.as_mut() {
+        Some(settlement) => {
+            require!(
+                clock.slot <= settlement.deadline_slot,
+                PokerError::DisputeWindowClosed
+            );
+            if let Some(overruled) = settlement.overruled.take() {
+                settlement.total_bets = overruled.total_bets;
+                settlement.payouts = overruled.payouts;
+                settlement.second_payouts = overruled.second_payouts;
+                settlement.folded_mask = overruled.folded_mask;
+                settlement.voided = false;
+                settlement.deadline_slot = clock.slot + DISPUTE_WINDOW_SLOTS;
+                msg!("Game {} adjudication reverted", game.game_id);
+            }
+        }
+        None => require!(
+            game.stage == GameStage::Playing,
+            PokerError::InvalidGameStage
+        ),
+    }
+
+    msg!(
+        "Game {}: seat {} signed two actions at action {}",
+        game.game_id,
+        first.seat_index,
+        sequence
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ProveEquivocation<'info> {
+    #[account(
+        constraint = matches!(game.stage, GameStage::Playing | GameStage::PendingSettlement) @ PokerError::InvalidGameStage,
+        constraint = game.version == PokerGame::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub game: Account<'info, PokerGame>,
+
+    #[account(
+        mut,
+        seeds = [b"transcript", game.key().as_ref()],
+        bump = transcript.bump,
+        constraint = transcript.version == Transcript::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub transcript: Account<'info, Transcript>,
+
+    /// Pending settlement; required once the result has been proposed
+    #[account(
+        mut,
+        seeds = [b"settlement", game.key().as_ref()],
+        bump = settlement.bump,
+        constraint = settlement.version == Settlement::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub settlement: Option<Account<'info, Settlement>>,
+
+    /// CHECK: Instructions sysvar holding the player's Ed25519 signatures
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+}
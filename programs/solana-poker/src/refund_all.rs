@@ -1,9 +1,12 @@
 use anchor_lang::prelude::*;
 use crate::error::PokerError;
-use crate::state::{GameStage, PlayerSeat, PokerGame, PokerTable};
+use crate::state::{GameStage, LobbyPage, PlayerSeat, PokerGame, PokerTable};
 
 /// Backend refunds all players and clears table state
 ///
+/// Each player gets back their stack plus the blinds, antes, straddle and
+/// dead blinds start_game collected for the abandoned hand.
+///
 /// Remaining accounts: pairs of [player_seat, player_wallet] for each player.
 pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, RefundAll<'info>>) -> Result<()> {
     let table = &mut ctx.accounts.table;
@@ -13,7 +16,7 @@ pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, RefundAll<'info>>) -> R
 
     let remaining = &ctx.remaining_accounts;
     require!(
-        !remaining.is_empty() && remaining.len() % 2 == 0,
+        !remaining.is_empty() && remaining.len().is_multiple_of(2),
         PokerError::InvalidRefundAccounts
     );
 
@@ -28,6 +31,7 @@ pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, RefundAll<'info>>) -> R
         let player_wallet = &remaining[i + 1];
 
         let mut seat: Account<PlayerSeat> = Account::try_from(seat_info)?;
+        require!(seat.version == PlayerSeat::VERSION, PokerError::UnsupportedAccountVersion);
 
         let (expected, _) = Pubkey::find_program_address(
             &[b"player_seat", table_key.as_ref(), seat.player.as_ref()],
@@ -36,7 +40,8 @@ pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, RefundAll<'info>>) -> R
         require!(expected == *seat_info.key, PokerError::PlayerNotAtTable);
         require!(player_wallet.key == &seat.player, PokerError::PlayerNotAtTable);
 
-        let amount = seat.chips;
+        // Forced bets start_game took are handed back with the stack
+        let amount = seat.refund_amount().ok_or(PokerError::InvalidBetAmount)?;
         if amount > 0 {
             let cpi_accounts = anchor_lang::system_program::Transfer {
                 from: ctx.accounts.vault.to_account_info(),
@@ -50,12 +55,17 @@ pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, RefundAll<'info>>) -> R
         }
 
         seat.chips = 0;
+        seat.total_bet = 0;
+        table.occupied_seats &= !(1 << seat.seat_index);
         seat.close(player_wallet.clone())?;
         refunded = refunded.saturating_add(1);
     }
 
     table.player_count = table.player_count.saturating_sub(refunded);
     table.current_game = None;
+    ctx.accounts
+        .lobby_page
+        .set_player_count(table_key, table.player_count)?;
     game.stage = GameStage::Finished;
     game.pot = 0;
     game.winner_seat = None;
@@ -67,16 +77,29 @@ pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, RefundAll<'info>>) -> R
 
 #[derive(Accounts)]
 pub struct RefundAll<'info> {
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = !table.confidential @ PokerError::ConfidentialTable,
+        constraint = table.version == PokerTable::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
     pub table: Account<'info, PokerTable>,
 
     #[account(
         mut,
         close = backend,
-        constraint = game.table == table.key() @ PokerError::NoActiveGame
+        constraint = game.table == table.key() @ PokerError::NoActiveGame,
+        constraint = game.stage != GameStage::PendingSettlement @ PokerError::InvalidGameStage,
+        constraint = game.version == PokerGame::VERSION @ PokerError::UnsupportedAccountVersion
     )]
     pub game: Account<'info, PokerGame>,
 
+    #[account(
+        mut,
+        seeds = [b"lobby", table.lobby_page.to_le_bytes().as_ref()],
+        bump = lobby_page.bump
+    )]
+    pub lobby_page: Account<'info, LobbyPage>,
+
     /// CHECK: Vault PDA to pay refunds from
     #[account(
         mut,
@@ -0,0 +1,102 @@
+use crate::constants::STUCK_HAND_SLOTS;
+use crate::error::PokerError;
+use crate::state::{GameStage, PlayerSeat, PokerGame, PokerTable};
+use anchor_lang::prelude::*;
+
+/// Abandon a confidential hand that cannot finish
+///
+/// Confidential stacks only change at settlement, so abandoning the hand
+/// before a result is proposed gives every player back their whole stack,
+/// forced bets included, by leaving it as it is: the forced bets start_game
+/// recorded on the seats are cleared and the table is free for the next
+/// hand. Players cash out through leave_table_confidential as usual.
+///
+/// The backend may abandon the hand at any time before settling it; anyone
+/// may once STUCK_HAND_SLOTS have passed since start_game. Pass every
+/// dealt-in seat via remaining_accounts, in any order.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RefundHandConfidential<'info>>,
+) -> Result<()> {
+    let table = &mut ctx.accounts.table;
+    let game = &ctx.accounts.game;
+
+    let clock = Clock::get()?;
+    require!(
+        ctx.accounts.caller.key() == game.backend_account
+            || clock.slot > game.started_slot + STUCK_HAND_SLOTS,
+        PokerError::NotBackend
+    );
+    require!(
+        ctx.remaining_accounts.len() == game.player_count as usize,
+        PokerError::InvalidSeatAccounts
+    );
+
+    let table_key = table.key();
+    let mut seen_mask: u16 = 0;
+
+    for seat_info in ctx.remaining_accounts.iter() {
+        let mut seat: Account<PlayerSeat> = Account::try_from(seat_info)?;
+        require!(seat.version == PlayerSeat::VERSION, PokerError::UnsupportedAccountVersion);
+
+        let expected = Pubkey::create_program_address(
+            &[
+                b"player_seat",
+                table_key.as_ref(),
+                seat.player.as_ref(),
+                &[seat.bump],
+            ],
+            ctx.program_id,
+        )
+        .map_err(|_| PokerError::PlayerNotAtTable)?;
+        require!(expected == *seat_info.key, PokerError::PlayerNotAtTable);
+        require!(seat.game == game.key(), PokerError::SeatNotInGame);
+
+        let seat_bit = 1u16 << seat.seat_index;
+        require!(seen_mask & seat_bit == 0, PokerError::InvalidSeatAccounts);
+        seen_mask |= seat_bit;
+
+        seat.current_bet = 0;
+        seat.total_bet = 0;
+        seat.exit(&crate::ID)?;
+    }
+
+    table.current_game = None;
+
+    msg!(
+        "Game {} abandoned, {} stacks left as they were",
+        game.game_id,
+        game.player_count
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RefundHandConfidential<'info> {
+    #[account(
+        mut,
+        constraint = table.confidential @ PokerError::NotConfidentialTable,
+        constraint = table.version == PokerTable::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub table: Account<'info, PokerTable>,
+
+    #[account(
+        mut,
+        close = backend,
+        constraint = game.table == table.key() @ PokerError::NoActiveGame,
+        constraint = table.current_game == Some(game.key()) @ PokerError::NoActiveGame,
+        constraint = matches!(game.stage, GameStage::Waiting | GameStage::Playing) @ PokerError::InvalidGameStage,
+        constraint = game.version == PokerGame::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub game: Account<'info, PokerGame>,
+
+    /// CHECK: Backend that paid for the game, receives its rent
+    #[account(
+        mut,
+        constraint = backend.key() == game.backend_account @ PokerError::NotBackend
+    )]
+    pub backend: AccountInfo<'info>,
+
+    /// Backend, or anyone once the hand is stuck
+    pub caller: Signer<'info>,
+}
@@ -1,4 +1,6 @@
+use crate::constants::INCO_AUTHORITY_SEED;
 use crate::error::PokerError;
+use crate::inco_authority::top_up;
 use crate::state::{PokerGame, PokerTable};
 use anchor_lang::prelude::*;
 use inco_lightning::cpi::accounts::Allow;
@@ -10,41 +12,53 @@ use inco_lightning::program::IncoLightning;
 /// This instruction allows the backend account to decrypt all community cards
 /// so it can reveal them progressively (flop, turn, river) during off-chain gameplay.
 ///
-/// Requires 5 allowance accounts in remaining_accounts (one per community card).
+/// Requires 5 allowance accounts in remaining_accounts (one per community card),
+/// followed by one per redrawn second-board card when the board runs twice.
 pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, RevealCommunity<'info>>) -> Result<()> {
+    let game = &ctx.accounts.game;
     require!(
-        ctx.remaining_accounts.len() >= 5,
+        ctx.remaining_accounts.len() >= 5 + game.second_board_cards(),
         PokerError::MissingAllowanceAccounts
     );
 
-    let game = &ctx.accounts.game;
-
     // Validate cards have been processed
     require!(game.cards_processed, PokerError::CardsNotProcessed);
 
     let cpi_program = ctx.accounts.inco_lightning_program.to_account_info();
-    let authority = ctx.accounts.backend.to_account_info();
+    let authority = ctx.accounts.inco_authority.to_account_info();
     let backend = ctx.accounts.backend.to_account_info();
+    let system_program = ctx.accounts.system_program.to_account_info();
+    let seeds = &[INCO_AUTHORITY_SEED, &[ctx.bumps.inco_authority]];
+    let signer = &[&seeds[..]];
 
-    // Allow backend to decrypt all 5 community cards
-    for i in 0..5 {
-        let handle = game.community_cards[i];
+    top_up(&authority, &backend, &system_program)?;
+
+    // Allow backend to decrypt all 5 community cards, then the second board
+    let second_board = &game.second_board[game.second_board_from as usize..];
+    let handles = game
+        .community_cards
+        .iter()
+        .chain(second_board.iter().take(game.second_board_cards()));
+    for (i, &handle) in handles.enumerate() {
         let allowance_acc = &ctx.remaining_accounts[i];
 
-        let cpi_ctx = CpiContext::new(
+        let cpi_ctx = CpiContext::new_with_signer(
             cpi_program.clone(),
             Allow {
                 allowance_account: allowance_acc.clone(),
                 signer: authority.clone(),
                 allowed_address: backend.clone(),
-                system_program: ctx.accounts.system_program.to_account_info(),
+                system_program: system_program.clone(),
             },
+            signer,
         );
 
         allow(cpi_ctx, handle.0, true, ctx.accounts.backend.key())?;
         msg!("Allowed community card {} decrypt for backend", i);
     }
 
+    top_up(&authority, &backend, &system_program)?;
+
     msg!(
         "All 5 community cards revealed to backend: {}",
         ctx.accounts.backend.key()
@@ -56,13 +70,14 @@ pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, RevealCommunity<'info>>) -
 #[derive(Accounts)]
 pub struct RevealCommunity<'info> {
     #[account(
-        constraint = table.backend == backend.key() @ PokerError::NotBackend
+        constraint = table.version == PokerTable::VERSION @ PokerError::UnsupportedAccountVersion
     )]
     pub table: Account<'info, PokerTable>,
 
     #[account(
         constraint = game.table == table.key() @ PokerError::NoActiveGame,
-        constraint = game.cards_processed @ PokerError::CardsNotProcessed
+        constraint = game.cards_processed @ PokerError::CardsNotProcessed,
+        constraint = game.version == PokerGame::VERSION @ PokerError::UnsupportedAccountVersion
     )]
     pub game: Account<'info, PokerGame>,
 
@@ -73,6 +88,14 @@ pub struct RevealCommunity<'info> {
     )]
     pub backend: Signer<'info>,
 
+    /// CHECK: Program PDA that owns the card handles
+    #[account(
+        mut,
+        seeds = [INCO_AUTHORITY_SEED],
+        bump
+    )]
+    pub inco_authority: AccountInfo<'info>,
+
     pub inco_lightning_program: Program<'info, IncoLightning>,
 
     pub system_program: Program<'info, System>,
@@ -0,0 +1,83 @@
+use crate::constants::INCO_AUTHORITY_SEED;
+use crate::error::PokerError;
+use crate::inco_authority::top_up;
+use crate::state::{PokerGame, PokerTable};
+use anchor_lang::prelude::*;
+use inco_lightning::cpi::accounts::Allow;
+use inco_lightning::cpi::allow;
+use inco_lightning::program::IncoLightning;
+
+/// Backend gets decrypt access to the drawn batch's duplicate flag
+///
+/// The flag handle only exists once process_cards_batch has run, so its
+/// allowance account is derived (and passed) afterwards.
+///
+/// Requires 1 allowance account in remaining_accounts.
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, RevealDuplicateFlag<'info>>) -> Result<()> {
+    require!(
+        !ctx.remaining_accounts.is_empty(),
+        PokerError::MissingAllowanceAccounts
+    );
+
+    let game = &ctx.accounts.game;
+    require!(game.batch_pending, PokerError::NoBatchPending);
+
+    let cpi_program = ctx.accounts.inco_lightning_program.to_account_info();
+    let authority = ctx.accounts.inco_authority.to_account_info();
+    let backend = ctx.accounts.backend.to_account_info();
+    let system_program = ctx.accounts.system_program.to_account_info();
+    let seeds = &[INCO_AUTHORITY_SEED, &[ctx.bumps.inco_authority]];
+    let signer = &[&seeds[..]];
+
+    top_up(&authority, &backend, &system_program)?;
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        cpi_program,
+        Allow {
+            allowance_account: ctx.remaining_accounts[0].clone(),
+            signer: authority.clone(),
+            allowed_address: backend.clone(),
+            system_program: system_program.clone(),
+        },
+        signer,
+    );
+    allow(cpi_ctx, game.duplicate_flag.0, true, backend.key())?;
+
+    top_up(&authority, &backend, &system_program)?;
+
+    msg!("Allowed duplicate flag of batch {} for backend", game.next_batch);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RevealDuplicateFlag<'info> {
+    #[account(
+        constraint = table.version == PokerTable::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub table: Account<'info, PokerTable>,
+
+    #[account(
+        constraint = game.table == table.key() @ PokerError::NoActiveGame,
+        constraint = game.is_dealing() @ PokerError::InvalidGameStage,
+        constraint = game.version == PokerGame::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub game: Account<'info, PokerGame>,
+
+    #[account(
+        mut,
+        constraint = backend.key() == game.backend_account @ PokerError::NotBackend
+    )]
+    pub backend: Signer<'info>,
+
+    /// CHECK: Program PDA that owns the card handles
+    #[account(
+        mut,
+        seeds = [INCO_AUTHORITY_SEED],
+        bump
+    )]
+    pub inco_authority: AccountInfo<'info>,
+
+    pub inco_lightning_program: Program<'info, IncoLightning>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -1,4 +1,6 @@
+use crate::constants::INCO_AUTHORITY_SEED;
 use crate::error::PokerError;
+use crate::inco_authority::top_up;
 use crate::state::{PlayerSeat, PokerGame, PokerTable};
 use anchor_lang::prelude::*;
 use inco_lightning::cpi::accounts::Allow;
@@ -7,114 +9,111 @@ use inco_lightning::program::IncoLightning;
 
 /// Player reveals their hand by granting themselves decrypt permission
 ///
-/// The player calls this after cards have been processed to get access
-/// to their specific hole cards based on the on-chain shuffle.
+/// Self-service: the player signs, and the program's Inco authority PDA
+/// (which owns the card handles) allows the player's own hole cards only,
+/// so neither the backend nor anyone else can hand out a seat's cards.
+///
+/// Requires one allowance account per hole card in remaining_accounts
+/// (2, or 4 in Omaha).
 pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, RevealHand<'info>>) -> Result<()> {
     let game = &ctx.accounts.game;
+    require!(
+        ctx.remaining_accounts.len() >= game.hole_cards_per_player(),
+        PokerError::MissingAllowanceAccounts
+    );
+
     let seat = &ctx.accounts.player_seat;
-    let player = &ctx.accounts.player;
 
     // Validate game has processed cards
     require!(game.cards_processed, PokerError::CardsNotProcessed);
 
-    // Validate seat belongs to this player
-    require!(seat.player == player.key(), PokerError::PlayerNotAtTable);
+    // Validate seat was dealt into this game
+    require!(seat.game == game.key(), PokerError::SeatNotInGame);
 
     // Validate player hasn't folded
     require!(!seat.is_folded, PokerError::PlayerFolded);
 
     let seat_index = seat.seat_index;
 
-    // Find which card pair is assigned to this seat
-    // shuffled_indices[pair_index] = seat_index
-    let mut pair_index: usize = 99;
-    for (i, &assigned_seat) in game.shuffled_indices.iter().enumerate() {
-        if assigned_seat == seat_index {
-            pair_index = i;
-            break;
-        }
-    }
-
-    // Validate seat is in the shuffled mapping
-    require!(pair_index < 5, PokerError::InvalidGameStage);
+    // Find which hand is assigned to this seat
+    // shuffled_indices[hand_index] = seat_index
+    let hand_index = game
+        .hand_index(seat_index)
+        .ok_or(PokerError::SeatNotInGame)?;
 
-    let card_1_idx = pair_index * 2;
-    let card_2_idx = pair_index * 2 + 1;
-
-    let handle_1 = game.deal_cards[card_1_idx];
-    let handle_2 = game.deal_cards[card_2_idx];
+    let per_seat = game.hole_cards_per_player();
+    let first_idx = hand_index * per_seat;
+    let handles = &game.deal_cards[first_idx..first_idx + per_seat];
 
     msg!(
-        "Revealing hand for seat {} (pair idx {}): slots {}, {}",
+        "Revealing hand for seat {} (hand idx {}): slots {}..{}",
         seat_index,
-        pair_index,
-        card_1_idx,
-        card_2_idx
+        hand_index,
+        first_idx,
+        first_idx + per_seat
     );
 
-    // CPI to Inco to allow access (backend is the handle owner)
+    // CPI to Inco to allow access (program PDA is the handle owner)
     let cpi_program = ctx.accounts.inco_lightning_program.to_account_info();
-    let authority = ctx.accounts.backend.to_account_info();
+    let authority = ctx.accounts.inco_authority.to_account_info();
     let allowed_player = ctx.accounts.player.to_account_info();
+    let system_program = ctx.accounts.system_program.to_account_info();
+    let seeds = &[INCO_AUTHORITY_SEED, &[ctx.bumps.inco_authority]];
+    let signer = &[&seeds[..]];
 
-    // Allow Card 1
-    if ctx.remaining_accounts.len() >= 1 {
-        let allowance_acc = &ctx.remaining_accounts[0];
-        let cpi_ctx = CpiContext::new(
-            cpi_program.clone(),
-            Allow {
-                allowance_account: allowance_acc.clone(),
-                signer: authority.clone(),
-                allowed_address: allowed_player.clone(),
-                system_program: ctx.accounts.system_program.to_account_info(),
-            },
-        );
-        allow(cpi_ctx, handle_1.0, true, player.key())?;
-        msg!("Allowed card 1 decrypt access");
-    }
+    top_up(&authority, &allowed_player, &system_program)?;
 
-    // Allow Card 2
-    if ctx.remaining_accounts.len() >= 2 {
-        let allowance_acc = &ctx.remaining_accounts[1];
-        let cpi_ctx = CpiContext::new(
+    for (i, handle) in handles.iter().enumerate() {
+        let cpi_ctx = CpiContext::new_with_signer(
             cpi_program.clone(),
             Allow {
-                allowance_account: allowance_acc.clone(),
+                allowance_account: ctx.remaining_accounts[i].clone(),
                 signer: authority.clone(),
                 allowed_address: allowed_player.clone(),
-                system_program: ctx.accounts.system_program.to_account_info(),
+                system_program: system_program.clone(),
             },
+            signer,
         );
-        allow(cpi_ctx, handle_2.0, true, player.key())?;
-        msg!("Allowed card 2 decrypt access");
+        allow(cpi_ctx, handle.0, true, ctx.accounts.player.key())?;
+        msg!("Allowed card {} decrypt access", i + 1);
     }
 
+    top_up(&authority, &allowed_player, &system_program)?;
+
     Ok(())
 }
 
 #[derive(Accounts)]
 pub struct RevealHand<'info> {
+    #[account(
+        constraint = table.version == PokerTable::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
     pub table: Account<'info, PokerTable>,
 
     #[account(
-        constraint = game.table == table.key() @ PokerError::NoActiveGame
+        constraint = game.table == table.key() @ PokerError::NoActiveGame,
+        constraint = game.version == PokerGame::VERSION @ PokerError::UnsupportedAccountVersion
     )]
     pub game: Account<'info, PokerGame>,
 
     #[account(
         seeds = [b"player_seat", table.key().as_ref(), player.key().as_ref()],
-        bump
+        bump = player_seat.bump,
+        constraint = player_seat.version == PlayerSeat::VERSION @ PokerError::UnsupportedAccountVersion
     )]
     pub player_seat: Account<'info, PlayerSeat>,
 
-    /// CHECK: player receiving decrypt access
-    pub player: UncheckedAccount<'info>,
+    /// Seated player receiving decrypt access to their own cards
+    #[account(mut)]
+    pub player: Signer<'info>,
 
+    /// CHECK: Program PDA that owns the card handles
     #[account(
         mut,
-        constraint = backend.key() == game.backend_account @ PokerError::NotBackend
+        seeds = [INCO_AUTHORITY_SEED],
+        bump
     )]
-    pub backend: Signer<'info>,
+    pub inco_authority: AccountInfo<'info>,
 
     pub inco_lightning_program: Program<'info, IncoLightning>,
 
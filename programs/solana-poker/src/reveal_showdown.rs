@@ -0,0 +1,155 @@
+use crate::constants::INCO_AUTHORITY_SEED;
+use crate::error::PokerError;
+use crate::inco_authority::top_up;
+use crate::state::{GameStage, PlayerSeat, PokerGame, PokerTable, Settlement, ShowChoice};
+use anchor_lang::prelude::*;
+use inco_lightning::cpi::accounts::Allow;
+use inco_lightning::cpi::allow;
+use inco_lightning::program::IncoLightning;
+
+/// Open a showdown seat's hole cards to the rest of the table
+///
+/// Only valid once betting is over, i.e. while the proposed settlement is
+/// in its dispute window, and only for a seat the settlement shows as
+/// still live (dealt in, not folded) with at least one other live seat.
+/// Seats whose player mucked stay hidden; a player who chose to show one
+/// card through set_show_choice opens only that card, every other live
+/// seat its whole hand.
+/// Permissionless: the payer funds the allowance accounts.
+///
+/// remaining_accounts holds per viewer: [viewer seat, viewer wallet, one
+/// allowance per opened hole card].
+/// Any seat at the table may be a viewer; call again for more viewers.
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, RevealShowdown<'info>>) -> Result<()> {
+    let game = &ctx.accounts.game;
+    let settlement = &ctx.accounts.settlement;
+    let shown = &ctx.accounts.shown_seat;
+
+    require!(
+        (settlement.dealt_mask & !settlement.folded_mask).count_ones() >= 2,
+        PokerError::NotAtShowdown
+    );
+    require!(shown.game == game.key(), PokerError::SeatNotInGame);
+    require!(settlement.is_live(shown.seat_index), PokerError::PlayerFolded);
+
+    let hole_cards = game
+        .get_player_hole_cards(shown.seat_index)
+        .ok_or(PokerError::SeatNotInGame)?;
+
+    require!(shown.show_choice != ShowChoice::Muck, PokerError::HandMucked);
+    let opened = match shown.show_choice {
+        ShowChoice::ShowFirst | ShowChoice::ShowSecond => {
+            shown.show_choice.shown_cards(hole_cards.len())
+        }
+        _ => 0..hole_cards.len(),
+    };
+    let handles = &hole_cards[opened];
+
+    let group_len = 2 + handles.len();
+    let remaining = ctx.remaining_accounts;
+    require!(
+        !remaining.is_empty() && remaining.len().is_multiple_of(group_len),
+        PokerError::MissingAllowanceAccounts
+    );
+
+    let table_key = ctx.accounts.table.key();
+    let cpi_program = ctx.accounts.inco_lightning_program.to_account_info();
+    let authority = ctx.accounts.inco_authority.to_account_info();
+    let payer = ctx.accounts.payer.to_account_info();
+    let system_program = ctx.accounts.system_program.to_account_info();
+    let seeds = &[INCO_AUTHORITY_SEED, &[ctx.bumps.inco_authority]];
+    let signer = &[&seeds[..]];
+
+    top_up(&authority, &payer, &system_program)?;
+
+    for group in remaining.chunks(group_len) {
+        let viewer_info = &group[0];
+        let viewer: Account<PlayerSeat> = Account::try_from(viewer_info)?;
+        require!(viewer.version == PlayerSeat::VERSION, PokerError::UnsupportedAccountVersion);
+
+        let expected = Pubkey::create_program_address(
+            &[
+                b"player_seat",
+                table_key.as_ref(),
+                viewer.player.as_ref(),
+                &[viewer.bump],
+            ],
+            ctx.program_id,
+        )
+        .map_err(|_| PokerError::PlayerNotAtTable)?;
+        require!(expected == *viewer_info.key, PokerError::PlayerNotAtTable);
+
+
+        // The viewer's wallet only needs to be named, not to sign
+        let viewer_wallet = &group[1];
+        require!(*viewer_wallet.key == viewer.player, PokerError::PlayerNotAtTable);
+
+        for (handle, allowance) in handles.iter().zip(group[2..].iter()) {
+            let cpi_ctx = CpiContext::new_with_signer(
+                cpi_program.clone(),
+                Allow {
+                    allowance_account: allowance.clone(),
+                    signer: authority.clone(),
+                    allowed_address: viewer_wallet.clone(),
+                    system_program: system_program.clone(),
+                },
+                signer,
+            );
+            allow(cpi_ctx, handle.0, true, viewer.player)?;
+        }
+
+        msg!(
+            "Showed seat {} hole cards to seat {}",
+            shown.seat_index,
+            viewer.seat_index
+        );
+    }
+
+    top_up(&authority, &payer, &system_program)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RevealShowdown<'info> {
+    #[account(
+        constraint = table.version == PokerTable::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub table: Account<'info, PokerTable>,
+
+    #[account(
+        constraint = game.table == table.key() @ PokerError::NoActiveGame,
+        constraint = game.stage == GameStage::PendingSettlement @ PokerError::InvalidGameStage,
+        constraint = game.version == PokerGame::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub game: Account<'info, PokerGame>,
+
+    #[account(
+        seeds = [b"settlement", game.key().as_ref()],
+        bump = settlement.bump,
+        constraint = settlement.version == Settlement::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub settlement: Account<'info, Settlement>,
+
+    /// Seat whose hole cards are shown
+    #[account(
+        constraint = shown_seat.version == PlayerSeat::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub shown_seat: Account<'info, PlayerSeat>,
+
+    /// Funds the allowance accounts
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Program PDA that owns the card handles
+    #[account(
+        mut,
+        seeds = [INCO_AUTHORITY_SEED],
+        bump
+    )]
+    pub inco_authority: AccountInfo<'info>,
+
+    pub inco_lightning_program: Program<'info, IncoLightning>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,82 @@
+use crate::constants::INCO_AUTHORITY_SEED;
+use crate::error::PokerError;
+use crate::inco_authority::top_up;
+use crate::state::{PlayerSeat, PokerTable};
+use anchor_lang::prelude::*;
+use inco_lightning::cpi::accounts::Allow;
+use inco_lightning::cpi::allow;
+use inco_lightning::program::IncoLightning;
+
+/// Player grants themselves decrypt access to their encrypted stack
+///
+/// Settling a confidential hand replaces the stack handle, so the player
+/// calls this again before cashing out with leave_table_confidential.
+///
+/// Requires 1 allowance account in remaining_accounts.
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, RevealStack<'info>>) -> Result<()> {
+    require!(
+        !ctx.remaining_accounts.is_empty(),
+        PokerError::MissingAllowanceAccounts
+    );
+
+    let seat = &ctx.accounts.player_seat;
+    require!(seat.enc_chips.0 != 0, PokerError::InsufficientChips);
+
+    let cpi_program = ctx.accounts.inco_lightning_program.to_account_info();
+    let authority = ctx.accounts.inco_authority.to_account_info();
+    let player = ctx.accounts.player.to_account_info();
+    let system_program = ctx.accounts.system_program.to_account_info();
+    let seeds = &[INCO_AUTHORITY_SEED, &[ctx.bumps.inco_authority]];
+    let signer = &[&seeds[..]];
+
+    top_up(&authority, &player, &system_program)?;
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        cpi_program,
+        Allow {
+            allowance_account: ctx.remaining_accounts[0].clone(),
+            signer: authority.clone(),
+            allowed_address: player.clone(),
+            system_program: system_program.clone(),
+        },
+        signer,
+    );
+    allow(cpi_ctx, seat.enc_chips.0, true, ctx.accounts.player.key())?;
+
+    top_up(&authority, &player, &system_program)?;
+
+    msg!("Allowed seat {} stack decrypt access", seat.seat_index);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RevealStack<'info> {
+    #[account(
+        constraint = table.confidential @ PokerError::NotConfidentialTable,
+        constraint = table.version == PokerTable::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub table: Account<'info, PokerTable>,
+
+    #[account(
+        seeds = [b"player_seat", table.key().as_ref(), player.key().as_ref()],
+        bump = player_seat.bump,
+        constraint = player_seat.version == PlayerSeat::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub player_seat: Account<'info, PlayerSeat>,
+
+    /// Seated player receiving decrypt access to their own stack
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    /// CHECK: Program PDA that owns the stack handles
+    #[account(
+        mut,
+        seeds = [INCO_AUTHORITY_SEED],
+        bump
+    )]
+    pub inco_authority: AccountInfo<'info>,
+
+    pub inco_lightning_program: Program<'info, IncoLightning>,
+
+    pub system_program: Program<'info, System>,
+}
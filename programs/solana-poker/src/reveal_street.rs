@@ -0,0 +1,149 @@
+use crate::constants::{COMMUNITY_CARDS, INCO_AUTHORITY_SEED, SPECTATOR_VIEWER};
+use crate::error::PokerError;
+use crate::inco_authority::top_up;
+use crate::state::{GameStage, PokerGame, PokerTable, Settlement, Transcript};
+use anchor_lang::prelude::*;
+use inco_lightning::cpi::accounts::Allow;
+use inco_lightning::cpi::allow;
+use inco_lightning::program::IncoLightning;
+
+/// Open the community cards of a reached street to spectators
+///
+/// `board_cards` is the number of board cards the street shows: 3 (flop),
+/// 4 (turn) or 5 (river). Cards not yet public are allowed to
+/// SPECTATOR_VIEWER, whose secret is published, so anyone can decrypt them
+/// without trusting the backend's relay. On the river of a board run
+/// twice, the second board's redrawn cards are opened as well.
+///
+/// Anyone may open a street the hand's transcript shows was reached.
+/// Hands played without a transcript have no on-chain proof of their
+/// streets, so there the backend is trusted, and only with the proposed
+/// settlement: it may open the board once the result shows a showdown
+/// (see Settlement::board_cards_dealt). The board opened so far is
+/// recorded in public_board.
+///
+/// Requires one allowance account in remaining_accounts per newly opened
+/// card, in board order.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, RevealStreet<'info>>,
+    board_cards: u8,
+) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let caller = &ctx.accounts.caller;
+
+    require!(
+        (3..=COMMUNITY_CARDS).contains(&board_cards) && board_cards > game.public_board,
+        PokerError::StreetNotReached
+    );
+
+    let reached = match (&ctx.accounts.transcript, &ctx.accounts.settlement) {
+        (Some(transcript), _) => transcript.board_cards_reached(),
+        (None, Some(settlement)) => {
+            require!(caller.key() == game.backend_account, PokerError::NotBackend);
+            settlement.board_cards_dealt()
+        }
+        (None, None) => 0,
+    };
+    require!(board_cards <= reached, PokerError::StreetNotReached);
+
+    let opened = game.public_board as usize..board_cards as usize;
+    let mut handles = game.community_cards[opened].to_vec();
+    if board_cards == COMMUNITY_CARDS {
+        let second_board = &game.second_board[game.second_board_from as usize..];
+        handles.extend(second_board.iter().take(game.second_board_cards()));
+    }
+    require!(
+        ctx.remaining_accounts.len() >= handles.len(),
+        PokerError::MissingAllowanceAccounts
+    );
+
+    let cpi_program = ctx.accounts.inco_lightning_program.to_account_info();
+    let authority = ctx.accounts.inco_authority.to_account_info();
+    let spectator = ctx.accounts.spectator.to_account_info();
+    let payer = caller.to_account_info();
+    let system_program = ctx.accounts.system_program.to_account_info();
+    let seeds = &[INCO_AUTHORITY_SEED, &[ctx.bumps.inco_authority]];
+    let signer = &[&seeds[..]];
+
+    top_up(&authority, &payer, &system_program)?;
+
+    for (handle, allowance) in handles.iter().zip(ctx.remaining_accounts.iter()) {
+        let cpi_ctx = CpiContext::new_with_signer(
+            cpi_program.clone(),
+            Allow {
+                allowance_account: allowance.clone(),
+                signer: authority.clone(),
+                allowed_address: spectator.clone(),
+                system_program: system_program.clone(),
+            },
+            signer,
+        );
+        allow(cpi_ctx, handle.0, true, SPECTATOR_VIEWER)?;
+    }
+
+    top_up(&authority, &payer, &system_program)?;
+
+    msg!(
+        "Game {} board cards {}..{} opened to spectators ({} handles)",
+        game.game_id,
+        game.public_board,
+        board_cards,
+        handles.len()
+    );
+    game.public_board = board_cards;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RevealStreet<'info> {
+    #[account(
+        constraint = table.version == PokerTable::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub table: Account<'info, PokerTable>,
+
+    #[account(
+        mut,
+        constraint = game.table == table.key() @ PokerError::NoActiveGame,
+        constraint = game.cards_processed @ PokerError::CardsNotProcessed,
+        constraint = matches!(game.stage, GameStage::Playing | GameStage::PendingSettlement) @ PokerError::InvalidGameStage,
+        constraint = game.version == PokerGame::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub game: Account<'info, PokerGame>,
+
+    /// Transcript proving the street was reached
+    #[account(
+        seeds = [b"transcript", game.key().as_ref()],
+        bump = transcript.bump,
+        constraint = transcript.version == Transcript::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub transcript: Option<Account<'info, Transcript>>,
+
+    /// Proposed result of a hand played without a transcript
+    #[account(
+        seeds = [b"settlement", game.key().as_ref()],
+        bump = settlement.bump,
+        constraint = settlement.version == Settlement::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub settlement: Option<Account<'info, Settlement>>,
+
+    /// Anyone with a transcript, otherwise the backend; funds the allowance accounts
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    /// CHECK: Public spectator key receiving decrypt access
+    #[account(address = SPECTATOR_VIEWER)]
+    pub spectator: AccountInfo<'info>,
+
+    /// CHECK: Program PDA that owns the card handles
+    #[account(
+        mut,
+        seeds = [INCO_AUTHORITY_SEED],
+        bump
+    )]
+    pub inco_authority: AccountInfo<'info>,
+
+    pub inco_lightning_program: Program<'info, IncoLightning>,
+
+    pub system_program: Program<'info, System>,
+}
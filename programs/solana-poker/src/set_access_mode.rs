@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+use crate::state::{AccessMode, LobbyPage, PokerTable};
+use crate::error::PokerError;
+
+/// Creator changes who may join
+///
+/// Seated players are unaffected. The table's lobby listing is refreshed
+/// with the new mode.
+pub fn handler(ctx: Context<SetAccessMode>, access_mode: AccessMode) -> Result<()> {
+    let table = &mut ctx.accounts.table;
+    table.access_mode = access_mode;
+
+    ctx.accounts
+        .lobby_page
+        .update(table.lobby_entry(table.key()))?;
+
+    msg!("Table {} access mode set to {:?}", table.table_id, access_mode);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetAccessMode<'info> {
+    #[account(
+        mut,
+        constraint = table.creator == creator.key() @ PokerError::NotCreator,
+        constraint = table.version == PokerTable::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub table: Account<'info, PokerTable>,
+
+    /// Lobby page listing the table
+    #[account(
+        mut,
+        seeds = [b"lobby", table.lobby_page.to_le_bytes().as_ref()],
+        bump = lobby_page.bump
+    )]
+    pub lobby_page: Account<'info, LobbyPage>,
+
+    pub creator: Signer<'info>,
+}
@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use crate::state::{PokerTable, PlayerSeat};
+use crate::error::PokerError;
+
+/// Player opts in to (or out of) the on-chain action clock
+///
+/// An opted-in seat can be timed out by timeout_action on its turns even
+/// when the backend never started the clock with arm_action_clock. The
+/// choice stays with the seat across hands.
+pub fn handler(ctx: Context<SetActionClock>, opt_in: bool) -> Result<()> {
+    let player_seat = &mut ctx.accounts.player_seat;
+    player_seat.clock_opt_in = opt_in;
+
+    msg!(
+        "Player {} at seat {} clock_opt_in={}",
+        ctx.accounts.player.key(),
+        player_seat.seat_index,
+        opt_in
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetActionClock<'info> {
+    #[account(
+        constraint = table.version == PokerTable::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub table: Account<'info, PokerTable>,
+
+    #[account(
+        mut,
+        seeds = [b"player_seat", table.key().as_ref(), player.key().as_ref()],
+        bump = player_seat.bump,
+        constraint = player_seat.version == PlayerSeat::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub player_seat: Account<'info, PlayerSeat>,
+
+    pub player: Signer<'info>,
+}
@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+use crate::error::PokerError;
+use crate::state::ProtocolConfig;
+
+/// Protocol admin flips the emergency pause switch
+///
+/// While paused, create_table, join_table, add_chips and start_game fail.
+/// Games already running can still settle, and leave_table and refund_all
+/// keep working so players can always withdraw.
+pub fn handler(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+    ctx.accounts.config.paused = paused;
+
+    msg!("Protocol paused={}", paused);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ PokerError::NotAdmin
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    pub admin: Signer<'info>,
+}
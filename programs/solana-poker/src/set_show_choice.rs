@@ -0,0 +1,163 @@
+use crate::constants::INCO_AUTHORITY_SEED;
+use crate::error::PokerError;
+use crate::inco_authority::top_up;
+use crate::state::{GameStage, PlayerSeat, PokerGame, PokerTable, ShowChoice, Transcript};
+use anchor_lang::prelude::*;
+use inco_lightning::cpi::accounts::Allow;
+use inco_lightning::cpi::allow;
+use inco_lightning::program::IncoLightning;
+
+/// Player shows one or all hole cards to the table, or mucks them
+///
+/// Usable once the hand has ended (settlement proposed) or, mid-hand,
+/// after the player's fold has been replayed in the transcript. The choice
+/// is recorded on the seat: mucked cards are skipped by reveal_showdown,
+/// and a choice can only be widened (one card to the whole hand), never
+/// taken back.
+///
+/// When showing, remaining_accounts holds per viewer: [viewer seat,
+/// viewer wallet, one allowance per shown card]. Call again with the same
+/// choice to show the cards to more seats.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SetShowChoice<'info>>,
+    choice: ShowChoice,
+) -> Result<()> {
+    let game = &ctx.accounts.game;
+    let seat = &mut ctx.accounts.player_seat;
+
+    require!(seat.game == game.key(), PokerError::SeatNotInGame);
+    require!(
+        choice != ShowChoice::Undecided && seat.show_choice.allows(choice),
+        PokerError::InvalidShowChoice
+    );
+
+    let folded = match &ctx.accounts.transcript {
+        Some(transcript) => transcript.folded_mask & (1u16 << seat.seat_index) != 0,
+        None => false,
+    };
+    require!(
+        game.stage == GameStage::PendingSettlement || folded,
+        PokerError::HandNotOver
+    );
+
+    let hole_cards = game
+        .get_player_hole_cards(seat.seat_index)
+        .ok_or(PokerError::SeatNotInGame)?;
+
+    seat.show_choice = choice;
+    msg!("Seat {} chose {:?}", seat.seat_index, choice);
+
+    let handles = &hole_cards[choice.shown_cards(hole_cards.len())];
+    if handles.is_empty() {
+        return Ok(());
+    }
+
+    let group_len = 2 + handles.len();
+    let remaining = ctx.remaining_accounts;
+    require!(
+        !remaining.is_empty() && remaining.len().is_multiple_of(group_len),
+        PokerError::MissingAllowanceAccounts
+    );
+
+    let table_key = ctx.accounts.table.key();
+    let shown_index = seat.seat_index;
+    let cpi_program = ctx.accounts.inco_lightning_program.to_account_info();
+    let authority = ctx.accounts.inco_authority.to_account_info();
+    let player = ctx.accounts.player.to_account_info();
+    let system_program = ctx.accounts.system_program.to_account_info();
+    let seeds = &[INCO_AUTHORITY_SEED, &[ctx.bumps.inco_authority]];
+    let signer = &[&seeds[..]];
+
+    top_up(&authority, &player, &system_program)?;
+
+    for group in remaining.chunks(group_len) {
+        let viewer_info = &group[0];
+        let viewer: Account<PlayerSeat> = Account::try_from(viewer_info)?;
+        require!(viewer.version == PlayerSeat::VERSION, PokerError::UnsupportedAccountVersion);
+
+        let expected = Pubkey::create_program_address(
+            &[
+                b"player_seat",
+                table_key.as_ref(),
+                viewer.player.as_ref(),
+                &[viewer.bump],
+            ],
+            ctx.program_id,
+        )
+        .map_err(|_| PokerError::PlayerNotAtTable)?;
+        require!(expected == *viewer_info.key, PokerError::PlayerNotAtTable);
+
+        let viewer_wallet = &group[1];
+        require!(*viewer_wallet.key == viewer.player, PokerError::PlayerNotAtTable);
+
+        for (handle, allowance) in handles.iter().zip(group[2..].iter()) {
+            let cpi_ctx = CpiContext::new_with_signer(
+                cpi_program.clone(),
+                Allow {
+                    allowance_account: allowance.clone(),
+                    signer: authority.clone(),
+                    allowed_address: viewer_wallet.clone(),
+                    system_program: system_program.clone(),
+                },
+                signer,
+            );
+            allow(cpi_ctx, handle.0, true, viewer.player)?;
+        }
+
+        msg!(
+            "Showed seat {} cards to seat {}",
+            shown_index,
+            viewer.seat_index
+        );
+    }
+
+    top_up(&authority, &player, &system_program)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetShowChoice<'info> {
+    #[account(
+        constraint = table.version == PokerTable::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub table: Account<'info, PokerTable>,
+
+    #[account(
+        constraint = game.table == table.key() @ PokerError::NoActiveGame,
+        constraint = game.version == PokerGame::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub game: Account<'info, PokerGame>,
+
+    #[account(
+        mut,
+        seeds = [b"player_seat", table.key().as_ref(), player.key().as_ref()],
+        bump = player_seat.bump,
+        constraint = player_seat.version == PlayerSeat::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub player_seat: Account<'info, PlayerSeat>,
+
+    /// Transcript proving a mid-hand fold; not needed once the hand ended
+    #[account(
+        seeds = [b"transcript", game.key().as_ref()],
+        bump = transcript.bump,
+        constraint = transcript.version == Transcript::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub transcript: Option<Account<'info, Transcript>>,
+
+    /// Seated player choosing what to show, funds the allowance accounts
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    /// CHECK: Program PDA that owns the card handles
+    #[account(
+        mut,
+        seeds = [INCO_AUTHORITY_SEED],
+        bump
+    )]
+    pub inco_authority: AccountInfo<'info>,
+
+    pub inco_lightning_program: Program<'info, IncoLightning>,
+
+    pub system_program: Program<'info, System>,
+}
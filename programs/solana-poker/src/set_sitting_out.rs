@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+use crate::state::{PokerTable, PlayerSeat};
+use crate::error::PokerError;
+
+/// Player sits out or sits back in
+///
+/// Takes effect from the next start_game. While sitting out the seat is not
+/// dealt in and blinds it passes are recorded; on sitting back in the owed
+/// blinds are posted as dead blinds at the next hand.
+pub fn handler(ctx: Context<SetSittingOut>, sitting_out: bool) -> Result<()> {
+    let player_seat = &mut ctx.accounts.player_seat;
+    player_seat.sitting_out = sitting_out;
+
+    msg!(
+        "Player {} at seat {} sitting_out={} (missed sb={}, bb={})",
+        ctx.accounts.player.key(),
+        player_seat.seat_index,
+        sitting_out,
+        player_seat.missed_small_blind,
+        player_seat.missed_big_blind
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetSittingOut<'info> {
+    #[account(
+        constraint = table.version == PokerTable::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub table: Account<'info, PokerTable>,
+
+    #[account(
+        mut,
+        seeds = [b"player_seat", table.key().as_ref(), player.key().as_ref()],
+        bump = player_seat.bump,
+        constraint = player_seat.version == PlayerSeat::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub player_seat: Account<'info, PlayerSeat>,
+
+    pub player: Signer<'info>,
+}
@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use crate::state::{PokerTable, PlayerSeat};
+use crate::error::PokerError;
+
+/// Player opts in to (or out of) straddling the next hand
+///
+/// Only used when the next start_game puts the seat in the table's
+/// straddle position; either way the choice is cleared once the hand
+/// starts, so every straddle is chosen for its own hand.
+pub fn handler(ctx: Context<SetStraddle>, straddle: bool) -> Result<()> {
+    let player_seat = &mut ctx.accounts.player_seat;
+    player_seat.straddle_opt_in = straddle;
+
+    msg!(
+        "Player {} at seat {} straddle_opt_in={}",
+        ctx.accounts.player.key(),
+        player_seat.seat_index,
+        straddle
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetStraddle<'info> {
+    #[account(
+        constraint = table.version == PokerTable::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub table: Account<'info, PokerTable>,
+
+    #[account(
+        mut,
+        seeds = [b"player_seat", table.key().as_ref(), player.key().as_ref()],
+        bump = player_seat.bump,
+        constraint = player_seat.version == PlayerSeat::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub player_seat: Account<'info, PlayerSeat>,
+
+    pub player: Signer<'info>,
+}
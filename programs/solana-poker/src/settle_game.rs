@@ -1,21 +1,38 @@
+use crate::constants::DISPUTE_WINDOW_SLOTS;
 use crate::error::PokerError;
-use crate::state::{GameStage, PlayerSeat, PokerGame, PokerTable};
+use crate::state::{GameStage, PlayerSeat, PokerGame, PokerTable, Settlement};
 use anchor_lang::prelude::*;
 
-/// Settle the game and pay out the winner
+/// Propose the hand result and open the dispute window
 ///
 /// Called by backend after off-chain gameplay completes.
-/// Accepts the final pot amount and winner seat index.
+/// Pass every dealt-in seat via remaining_accounts; total_bets[i] and
+/// payouts[i] describe remaining_accounts[i] for the whole hand, and
+/// folded_mask marks the seats that folded. When the board ran twice,
+/// second_payouts[i] is the part of payouts[i] won on the second board;
+/// otherwise it is empty.
 ///
 /// Flow:
 /// 1. Validate game is in Playing stage and cards are processed
-/// 2. Transfer pot from vault to winner's wallet
-/// 3. Update game state to Finished
-/// 4. Clear table's current_game reference
-pub fn handler(ctx: Context<SettleGame>, winner_seat_index: u8, final_pot: u64) -> Result<()> {
-    let table = &mut ctx.accounts.table;
+/// 2. Check each seat can cover its off-chain bets (total_bet beyond what
+///    start_game already took)
+/// 3. Check payouts exactly distribute the resulting pot, and when the
+///    board ran twice, that each board pays half of every pot
+/// 4. Record the result in the Settlement account and move the game to
+///    PendingSettlement
+///
+/// No chips move here: finalize_settlement applies the result once
+/// DISPUTE_WINDOW_SLOTS have passed; a challenge corrects the result first.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SettleGame<'info>>,
+    total_bets: Vec<u64>,
+    payouts: Vec<u64>,
+    second_payouts: Vec<u64>,
+    folded_mask: u16,
+) -> Result<()> {
+    let table = &ctx.accounts.table;
     let game = &mut ctx.accounts.game;
-    let winner_seat = &mut ctx.accounts.winner_seat;
+    let settlement = &mut ctx.accounts.settlement;
 
     // Validate game state
     require!(
@@ -23,90 +40,126 @@ pub fn handler(ctx: Context<SettleGame>, winner_seat_index: u8, final_pot: u64)
         PokerError::InvalidGameStage
     );
     require!(game.cards_processed, PokerError::CardsNotProcessed);
+
+    let seat_count = game.player_count as usize;
     require!(
-        winner_seat.seat_index == winner_seat_index,
-        PokerError::PlayerNotAtTable
+        ctx.remaining_accounts.len() == seat_count
+            && total_bets.len() == seat_count
+            && payouts.len() == seat_count
+            && second_payouts.len() == if game.run_twice { seat_count } else { 0 },
+        PokerError::InvalidSeatAccounts
     );
 
-    // Use final_pot from backend (includes all bets collected off-chain)
-    let payout_amount = final_pot;
-    let winner = winner_seat.player;
-
-    // Transfer pot to winner via vault PDA
     let table_key = table.key();
-    let seeds = &[b"vault", table_key.as_ref(), &[ctx.bumps.vault]];
-    let signer = &[&seeds[..]];
-
-    // Only transfer if there's something to pay
-    if payout_amount > 0 {
-        let cpi_accounts = anchor_lang::system_program::Transfer {
-            from: ctx.accounts.vault.to_account_info(),
-            to: ctx.accounts.winner_wallet.to_account_info(),
-        };
-        let cpi_program = ctx.accounts.system_program.to_account_info();
-
-        anchor_lang::system_program::transfer(
-            CpiContext::new_with_signer(cpi_program, cpi_accounts, signer),
-            payout_amount,
+    let mut pot = game.pot;
+    let mut seen_mask: u16 = 0;
+
+    // Check every seat can cover its remaining off-chain bets
+    for (i, seat_info) in ctx.remaining_accounts.iter().enumerate() {
+        let seat: Account<PlayerSeat> = Account::try_from(seat_info)?;
+        require!(seat.version == PlayerSeat::VERSION, PokerError::UnsupportedAccountVersion);
+
+        let expected = Pubkey::create_program_address(
+            &[
+                b"player_seat",
+                table_key.as_ref(),
+                seat.player.as_ref(),
+                &[seat.bump],
+            ],
+            ctx.program_id,
+        )
+        .map_err(|_| PokerError::PlayerNotAtTable)?;
+        require!(expected == *seat_info.key, PokerError::PlayerNotAtTable);
+        require!(seat.game == game.key(), PokerError::SeatNotInGame);
+
+        let seat_bit = 1u16 << seat.seat_index;
+        require!(seen_mask & seat_bit == 0, PokerError::InvalidSeatAccounts);
+        seen_mask |= seat_bit;
+
+        require!(
+            total_bets[i] >= seat.total_bet,
+            PokerError::InvalidBetAmount
+        );
+        let additional = total_bets[i] - seat.total_bet;
+        require!(seat.chips >= additional, PokerError::InsufficientChips);
+        pot = pot
+            .checked_add(additional)
+            .ok_or(PokerError::InvalidBetAmount)?;
+
+        // Folded seats cannot win anything back
+        require!(
+            folded_mask & seat_bit == 0 || payouts[i] == 0,
+            PokerError::InvalidPayouts
+        );
+
+        let idx = seat.seat_index as usize;
+        settlement.total_bets[idx] = total_bets[i];
+        settlement.payouts[idx] = payouts[i];
+        settlement.second_payouts[idx] = second_payouts.get(i).copied().unwrap_or(0);
+    }
+
+    require!(folded_mask & !seen_mask == 0, PokerError::InvalidSeatAccounts);
+
+    let total_payout = payouts
+        .iter()
+        .try_fold(0u64, |acc, &p| acc.checked_add(p))
+        .ok_or(PokerError::InvalidPayouts)?;
+    require!(total_payout == pot, PokerError::InvalidPayouts);
+    if game.run_twice {
+        Settlement::check_run_twice(
+            &settlement.total_bets,
+            &settlement.payouts,
+            &settlement.second_payouts,
+            seen_mask & !folded_mask,
         )?;
     }
 
-    // Update game state
-    game.winner_seat = Some(winner_seat_index);
-    game.stage = GameStage::Finished;
-    game.pot = 0;
-    game.payouts[winner_seat_index as usize] = payout_amount;
+    let clock = Clock::get()?;
+    settlement.version = Settlement::VERSION;
+    settlement.game = game.key();
+    settlement.dealt_mask = seen_mask;
+    settlement.folded_mask = folded_mask;
+    settlement.voided = false;
+    settlement.overruled = None;
+    settlement.deadline_slot = clock.slot + DISPUTE_WINDOW_SLOTS;
+    settlement.bump = ctx.bumps.settlement;
 
-    // Clear table's current game
-    table.current_game = None;
+    game.stage = GameStage::PendingSettlement;
 
     msg!(
-        "Game {} finished. Winner seat {} ({}) won {} lamports",
+        "Game {} result proposed. Pot {}, challenges open until slot {}",
         game.game_id,
-        winner_seat_index,
-        winner,
-        payout_amount
+        pot,
+        settlement.deadline_slot
     );
 
     Ok(())
 }
 
 #[derive(Accounts)]
-#[instruction(winner_seat_index: u8, final_pot: u64)]
 pub struct SettleGame<'info> {
     #[account(
-        mut
+        constraint = !table.confidential @ PokerError::ConfidentialTable,
+        constraint = table.version == PokerTable::VERSION @ PokerError::UnsupportedAccountVersion
     )]
     pub table: Account<'info, PokerTable>,
 
     #[account(
         mut,
-        close = backend,
         constraint = game.table == table.key() @ PokerError::NoActiveGame,
-        constraint = game.stage == GameStage::Playing @ PokerError::InvalidGameStage
+        constraint = game.stage == GameStage::Playing @ PokerError::InvalidGameStage,
+        constraint = game.version == PokerGame::VERSION @ PokerError::UnsupportedAccountVersion
     )]
     pub game: Account<'info, PokerGame>,
 
     #[account(
-        mut,
-        constraint = winner_seat.seat_index == winner_seat_index @ PokerError::PlayerNotAtTable
-    )]
-    pub winner_seat: Account<'info, PlayerSeat>,
-
-    /// CHECK: Winner's wallet to receive payout
-    #[account(
-        mut,
-        constraint = winner_wallet.key() == winner_seat.player @ PokerError::PlayerNotAtTable
-    )]
-    pub winner_wallet: AccountInfo<'info>,
-
-    /// CHECK: Vault PDA to pay from
-    #[account(
-        mut,
-        seeds = [b"vault", table.key().as_ref()],
+        init,
+        payer = backend,
+        space = Settlement::LEN,
+        seeds = [b"settlement", game.key().as_ref()],
         bump
     )]
-    pub vault: AccountInfo<'info>,
+    pub settlement: Account<'info, Settlement>,
 
     #[account(
         mut,
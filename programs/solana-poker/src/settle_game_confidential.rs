@@ -0,0 +1,221 @@
+use crate::constants::{DISPUTE_WINDOW_SLOTS, INCO_AUTHORITY_SEED, MAX_PLAYERS};
+use crate::error::PokerError;
+use crate::inco_authority::top_up;
+use crate::state::{ConfidentialSettlement, GameStage, PlayerSeat, PokerGame, PokerTable};
+use anchor_lang::prelude::*;
+use inco_lightning::cpi::accounts::Operation;
+use inco_lightning::cpi::{self, e_add, e_eq, e_ge, e_select, new_euint128};
+use inco_lightning::program::IncoLightning;
+use inco_lightning::types::Euint128;
+
+/// Propose a confidential table's hand result and open the dispute window
+///
+/// Called by backend after off-chain gameplay completes. Pass every
+/// dealt-in seat via remaining_accounts; total_bets[i] and payouts[i] are
+/// client-encrypted inputs for remaining_accounts[i] covering the whole
+/// hand, and folded_mask marks the seats that folded.
+///
+/// start_game does not touch encrypted stacks, so the blinds, antes,
+/// straddle and dead blinds it recorded on each seat (total_bet) are part
+/// of that seat's bet here: a smaller bet is raised to them.
+///
+/// Flow:
+/// 1. Public deposits (buy-ins, add_chips) move into each encrypted stack
+/// 2. Each bet is raised to the seat's forced bets, then a bet the stack
+///    cannot cover is cut down to the stack (all-in)
+/// 3. Each payout is checked against the pot, so a wrapped-around payout
+///    cannot balance the sums; folded seats are paid nothing
+/// 4. The pot and the payouts are summed encrypted and whether they match
+///    is recorded with the result in the ConfidentialSettlement account;
+///    the game moves to PendingSettlement
+///
+/// Nothing is ever decrypted here and no stack changes beyond the deposits:
+/// finalize_settlement_confidential applies the result once
+/// DISPUTE_WINDOW_SLOTS have passed; challenge_settlement_confidential
+/// corrects it first. An unbalanced result voids the hand; a hand that
+/// cannot be settled at all is abandoned through refund_hand_confidential.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SettleGameConfidential<'info>>,
+    total_bets: Vec<Vec<u8>>,
+    payouts: Vec<Vec<u8>>,
+    folded_mask: u16,
+    input_type: u8,
+) -> Result<()> {
+    let table = &ctx.accounts.table;
+    let game = &mut ctx.accounts.game;
+    let settlement = &mut ctx.accounts.settlement;
+
+    // Validate game state
+    require!(
+        game.stage == GameStage::Playing,
+        PokerError::InvalidGameStage
+    );
+    require!(game.cards_processed, PokerError::CardsNotProcessed);
+
+    let seat_count = game.player_count as usize;
+    require!(
+        ctx.remaining_accounts.len() == seat_count
+            && total_bets.len() == seat_count
+            && payouts.len() == seat_count,
+        PokerError::InvalidSeatAccounts
+    );
+
+    let cpi_program = ctx.accounts.inco_lightning_program.to_account_info();
+    let authority = ctx.accounts.inco_authority.to_account_info();
+    let backend = ctx.accounts.backend.to_account_info();
+    let system_program = ctx.accounts.system_program.to_account_info();
+    let seeds = &[INCO_AUTHORITY_SEED, &[ctx.bumps.inco_authority]];
+    let signer = &[&seeds[..]];
+
+    let op_accounts = Operation {
+        signer: authority.clone(),
+    };
+    let op = || CpiContext::new_with_signer(cpi_program.clone(), op_accounts.clone(), signer);
+    top_up(&authority, &backend, &system_program)?;
+
+    let table_key = table.key();
+    let mut seen_mask: u16 = 0;
+    let zero: Euint128 = cpi::as_euint128(op(), 0)?;
+    let mut pot = zero;
+    let mut bets: Vec<(usize, Euint128)> = Vec::with_capacity(seat_count);
+
+    for (i, seat_info) in ctx.remaining_accounts.iter().enumerate() {
+        let mut seat: Account<PlayerSeat> = Account::try_from(seat_info)?;
+        require!(seat.version == PlayerSeat::VERSION, PokerError::UnsupportedAccountVersion);
+
+        let expected = Pubkey::create_program_address(
+            &[
+                b"player_seat",
+                table_key.as_ref(),
+                seat.player.as_ref(),
+                &[seat.bump],
+            ],
+            ctx.program_id,
+        )
+        .map_err(|_| PokerError::PlayerNotAtTable)?;
+        require!(expected == *seat_info.key, PokerError::PlayerNotAtTable);
+        require!(seat.game == game.key(), PokerError::SeatNotInGame);
+
+        let seat_bit = 1u16 << seat.seat_index;
+        require!(seen_mask & seat_bit == 0, PokerError::InvalidSeatAccounts);
+        seen_mask |= seat_bit;
+
+        // Deposits made since the last hand join the encrypted stack
+        if seat.chips > 0 {
+            let deposit = cpi::as_euint128(op(), seat.chips as u128)?;
+            seat.enc_chips = if seat.enc_chips.0 == 0 {
+                deposit
+            } else {
+                e_add(op(), seat.enc_chips, deposit, 0)?
+            };
+            seat.chips = 0;
+            seat.exit(&crate::ID)?;
+        }
+
+        let bet: Euint128 = new_euint128(op(), total_bets[i].clone(), input_type)?;
+
+        // Never less than the forced bets start_game recorded
+        let forced = cpi::as_euint128(op(), seat.total_bet as u128)?;
+        let posts_forced = e_ge(op(), bet, forced, 0)?;
+        let bet = e_select(op(), posts_forced, bet, forced, 0)?;
+
+        // A bet the stack cannot cover puts the seat all-in
+        let covered = e_ge(op(), seat.enc_chips, bet, 0)?;
+        let bet = e_select(op(), covered, bet, seat.enc_chips, 0)?;
+
+        pot = e_add(op(), pot, bet, 0)?;
+        bets.push((seat.seat_index as usize, bet));
+    }
+
+    require!(folded_mask & !seen_mask == 0, PokerError::InvalidSeatAccounts);
+
+    // A payout above the pot counts as pot + 1, so the sum can no longer
+    // match the pot however far the encrypted addition wraps
+    let one = cpi::as_euint128(op(), 1)?;
+    let over = e_add(op(), pot, one, 0)?;
+    let mut paid = zero;
+    for (i, &(idx, bet)) in bets.iter().enumerate() {
+        // Folded seats cannot win anything back
+        let payout = if folded_mask & (1u16 << idx) != 0 {
+            zero
+        } else {
+            let payout: Euint128 = new_euint128(op(), payouts[i].clone(), input_type)?;
+            let fits = e_ge(op(), pot, payout, 0)?;
+            e_select(op(), fits, payout, over, 0)?
+        };
+
+        paid = e_add(op(), paid, payout, 0)?;
+        settlement.total_bets[idx] = bet;
+        settlement.payouts[idx] = payout;
+    }
+
+    // Payouts must distribute exactly the pot, or nothing changes
+    let balanced = e_eq(op(), pot, paid, 0)?;
+
+    top_up(&authority, &backend, &system_program)?;
+
+    let clock = Clock::get()?;
+    settlement.version = ConfidentialSettlement::VERSION;
+    settlement.game = game.key();
+    settlement.balanced = balanced;
+    settlement.dealt_mask = seen_mask;
+    settlement.folded_mask = folded_mask;
+    settlement.challenged_by = [0; MAX_PLAYERS as usize];
+    settlement.deadline_slot = clock.slot + DISPUTE_WINDOW_SLOTS;
+    settlement.bump = ctx.bumps.settlement;
+
+    game.stage = GameStage::PendingSettlement;
+
+    msg!(
+        "Game {} result proposed confidentially for {} seats, challenges open until slot {}",
+        game.game_id,
+        seat_count,
+        settlement.deadline_slot
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SettleGameConfidential<'info> {
+    #[account(
+        constraint = table.confidential @ PokerError::NotConfidentialTable,
+        constraint = table.version == PokerTable::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub table: Account<'info, PokerTable>,
+
+    #[account(
+        mut,
+        constraint = game.table == table.key() @ PokerError::NoActiveGame,
+        constraint = game.stage == GameStage::Playing @ PokerError::InvalidGameStage,
+        constraint = game.version == PokerGame::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub game: Account<'info, PokerGame>,
+
+    #[account(
+        init,
+        payer = backend,
+        space = ConfidentialSettlement::LEN,
+        seeds = [b"confidential_settlement", game.key().as_ref()],
+        bump
+    )]
+    pub settlement: Account<'info, ConfidentialSettlement>,
+
+    #[account(
+        mut,
+        constraint = backend.key() == game.backend_account @ PokerError::NotBackend
+    )]
+    pub backend: Signer<'info>,
+
+    /// CHECK: Program PDA that owns the stack handles
+    #[account(
+        mut,
+        seeds = [INCO_AUTHORITY_SEED],
+        bump
+    )]
+    pub inco_authority: AccountInfo<'info>,
+
+    pub inco_lightning_program: Program<'info, IncoLightning>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,236 @@
+use crate::constants::MAX_PLAYERS;
+use crate::ed25519::signers_of;
+use crate::error::PokerError;
+use crate::state::{GameStage, PlayerSeat, PokerGame, PokerTable, Settlement};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use anchor_lang::solana_program::sysvar;
+
+/// Domain tag prefixed to every signed settlement result
+pub const SETTLE_DOMAIN: &[u8] = b"solana-poker:settle";
+
+/// Digest players sign to agree on a hand result
+///
+/// sha256(SETTLE_DOMAIN || game || game_id (le) || folded_mask (le) ||
+/// for each dealt-in seat by ascending seat_index:
+/// seat_index || total_bet (le) || payout (le) || second_payout (le))
+///
+/// second_payout is zero unless the board ran twice.
+pub fn result_digest(
+    game: &Pubkey,
+    game_id: u64,
+    folded_mask: u16,
+    results: &[(u8, u64, u64, u64)],
+) -> [u8; 32] {
+    let mut message = Vec::with_capacity(42 + results.len() * 25);
+    message.extend_from_slice(game.as_ref());
+    message.extend_from_slice(&game_id.to_le_bytes());
+    message.extend_from_slice(&folded_mask.to_le_bytes());
+    for &(seat_index, total_bet, payout, second_payout) in results {
+        message.push(seat_index);
+        message.extend_from_slice(&total_bet.to_le_bytes());
+        message.extend_from_slice(&payout.to_le_bytes());
+        message.extend_from_slice(&second_payout.to_le_bytes());
+    }
+    hashv(&[SETTLE_DOMAIN, &message]).to_bytes()
+}
+
+/// Settle the game immediately on unanimous player agreement
+///
+/// Every dealt-in player signs result_digest off-chain and the
+/// transaction carries those signatures as Ed25519 precompile instructions
+/// ahead of this one. Folded players sign too: the digest carries
+/// folded_mask, so nobody is marked folded, or charged, without agreeing.
+/// No dispute window is needed, and anyone (backend or player) can submit
+/// it. Pass every dealt-in seat via remaining_accounts; total_bets[i] and
+/// payouts[i] describe remaining_accounts[i]. When the board ran twice,
+/// second_payouts[i] is the part of payouts[i] won on the second board and
+/// each board must pay half of every pot; otherwise it is empty.
+///
+/// Transaction size limits the signatures that fit; large showdowns still
+/// go through settle_game.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SettleGameSigned<'info>>,
+    total_bets: Vec<u64>,
+    payouts: Vec<u64>,
+    second_payouts: Vec<u64>,
+    folded_mask: u16,
+) -> Result<()> {
+    let table = &mut ctx.accounts.table;
+    let game = &mut ctx.accounts.game;
+
+    // Validate game state
+    require!(
+        game.stage == GameStage::Playing,
+        PokerError::InvalidGameStage
+    );
+    require!(game.cards_processed, PokerError::CardsNotProcessed);
+
+    let seat_count = game.player_count as usize;
+    require!(
+        ctx.remaining_accounts.len() == seat_count
+            && total_bets.len() == seat_count
+            && payouts.len() == seat_count
+            && second_payouts.len() == if game.run_twice { seat_count } else { 0 },
+        PokerError::InvalidSeatAccounts
+    );
+
+    let table_key = table.key();
+    let mut pot = game.pot;
+    let mut seen_mask: u16 = 0;
+    let mut seats: Vec<Account<PlayerSeat>> = Vec::with_capacity(seat_count);
+
+    // Collect the remaining off-chain bets into the pot
+    for (i, seat_info) in ctx.remaining_accounts.iter().enumerate() {
+        let mut seat: Account<PlayerSeat> = Account::try_from(seat_info)?;
+        require!(seat.version == PlayerSeat::VERSION, PokerError::UnsupportedAccountVersion);
+
+        let expected = Pubkey::create_program_address(
+            &[
+                b"player_seat",
+                table_key.as_ref(),
+                seat.player.as_ref(),
+                &[seat.bump],
+            ],
+            ctx.program_id,
+        )
+        .map_err(|_| PokerError::PlayerNotAtTable)?;
+        require!(expected == *seat_info.key, PokerError::PlayerNotAtTable);
+        require!(seat.game == game.key(), PokerError::SeatNotInGame);
+
+        let seat_bit = 1u16 << seat.seat_index;
+        require!(seen_mask & seat_bit == 0, PokerError::InvalidSeatAccounts);
+        seen_mask |= seat_bit;
+
+        require!(
+            total_bets[i] >= seat.total_bet,
+            PokerError::InvalidBetAmount
+        );
+        let additional = total_bets[i] - seat.total_bet;
+        require!(seat.chips >= additional, PokerError::InsufficientChips);
+        seat.chips -= additional;
+        pot = pot
+            .checked_add(additional)
+            .ok_or(PokerError::InvalidBetAmount)?;
+
+        // Folded seats cannot win anything back
+        require!(
+            folded_mask & seat_bit == 0 || payouts[i] == 0,
+            PokerError::InvalidPayouts
+        );
+
+        seats.push(seat);
+    }
+    require!(folded_mask & !seen_mask == 0, PokerError::InvalidSeatAccounts);
+
+    let total_payout = payouts
+        .iter()
+        .try_fold(0u64, |acc, &p| acc.checked_add(p))
+        .ok_or(PokerError::InvalidPayouts)?;
+    require!(total_payout == pot, PokerError::InvalidPayouts);
+
+    let mut results: Vec<(u8, u64, u64, u64)> = seats
+        .iter()
+        .enumerate()
+        .map(|(i, seat)| {
+            let second = second_payouts.get(i).copied().unwrap_or(0);
+            (seat.seat_index, total_bets[i], payouts[i], second)
+        })
+        .collect();
+    results.sort_unstable_by_key(|&(seat_index, _, _, _)| seat_index);
+
+    if game.run_twice {
+        let mut seat_bets = [0u64; MAX_PLAYERS as usize];
+        let mut seat_payouts = [0u64; MAX_PLAYERS as usize];
+        let mut seat_second_payouts = [0u64; MAX_PLAYERS as usize];
+        for &(seat_index, bet, payout, second) in results.iter() {
+            let idx = seat_index as usize;
+            seat_bets[idx] = bet;
+            seat_payouts[idx] = payout;
+            seat_second_payouts[idx] = second;
+        }
+        Settlement::check_run_twice(
+            &seat_bets,
+            &seat_payouts,
+            &seat_second_payouts,
+            seen_mask & !folded_mask,
+        )?;
+    }
+
+    // ===== SIGNATURES =====
+    let digest = result_digest(&game.key(), game.game_id, folded_mask, &results);
+
+    let signers = signers_of(&ctx.accounts.instructions, &digest)?;
+    for seat in seats.iter() {
+        require!(
+            signers.contains(&seat.player),
+            PokerError::MissingPlayerSignature
+        );
+    }
+
+    // Credit winnings to seat stacks
+    let mut winner: Option<(u8, u64)> = None;
+    for (seat, &payout) in seats.iter_mut().zip(payouts.iter()) {
+        seat.chips += payout;
+        seat.current_bet = 0;
+        seat.total_bet = 0;
+        game.payouts[seat.seat_index as usize] = payout;
+
+        let is_top = match winner {
+            Some((_, best)) => payout > best,
+            None => payout > 0,
+        };
+        if is_top {
+            winner = Some((seat.seat_index, payout));
+        }
+
+        seat.exit(&crate::ID)?;
+    }
+
+    // Update game state
+    game.winner_seat = winner.map(|(seat_index, _)| seat_index);
+    game.stage = GameStage::Finished;
+    game.pot = 0;
+
+    // Clear table's current game
+    table.current_game = None;
+
+    msg!(
+        "Game {} settled by player agreement. Pot {}, top winner seat {:?}",
+        game.game_id,
+        pot,
+        game.winner_seat
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SettleGameSigned<'info> {
+    #[account(
+        mut,
+        constraint = !table.confidential @ PokerError::ConfidentialTable,
+        constraint = table.version == PokerTable::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub table: Account<'info, PokerTable>,
+
+    #[account(
+        mut,
+        close = backend,
+        constraint = game.table == table.key() @ PokerError::NoActiveGame,
+        constraint = game.stage == GameStage::Playing @ PokerError::InvalidGameStage,
+        constraint = game.version == PokerGame::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub game: Account<'info, PokerGame>,
+
+    /// CHECK: Backend that paid for the game, receives its rent
+    #[account(
+        mut,
+        constraint = backend.key() == game.backend_account @ PokerError::NotBackend
+    )]
+    pub backend: AccountInfo<'info>,
+
+    /// CHECK: Instructions sysvar holding the players' Ed25519 signatures
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+}
@@ -1,22 +1,27 @@
-use crate::constants::MIN_PLAYERS;
+use crate::constants::{EMPTY_SEAT, MIN_PLAYERS, STRADDLE_MULTIPLIER, TIME_BANK_SLOTS};
 use crate::error::PokerError;
-use crate::state::{GameStage, PlayerSeat, PokerGame, PokerTable};
+use crate::state::{GameStage, PlayerSeat, PokerGame, PokerTable, ProtocolConfig, Straddle};
 use anchor_lang::prelude::*;
 use inco_lightning::types::Euint128;
 
 /// Start a new game at the table
 ///
 /// Flow:
-/// 1. Admin calls start_game with blind amounts
-/// 2. Blind bets are collected from small blind and big blind players
-/// 3. Game is initialized in Waiting stage
-/// 4. Next: process_cards (8 batches) to shuffle and deal
-/// 5. After cards processed: backend manages off-chain gameplay
-/// 6. Finally: settle_game to pay winner
+/// 1. Admin calls start_game with blind amounts and every seat at the table
+/// 2. Every seat's per-hand state is reset; dealt-in seats are bound to the
+///    game, sitting-out and busted seats are skipped for dealing and blinds
+/// 3. Antes, blinds, an opted-in straddle and owed dead blinds are collected from
+///    dealt-in players (all-in when a stack cannot cover them) and the
+///    preflop action order is recorded; confidential tables only record
+///    them on the seats, to be taken encrypted at settlement
+/// 4. Game is initialized in Waiting stage
+/// 5. Next: process_cards (one batch per 2 cards) to shuffle and deal
+/// 6. After cards processed: backend manages off-chain gameplay
+/// 7. Finally: settle_game proposes the result, finalize_settlement pays it
+///    out once the dispute window closes
 pub fn handler<'info>(
     ctx: Context<'_, '_, 'info, 'info, StartGame<'info>>,
     game_id: u64,
-    backend_account: Pubkey,
     small_blind_amount: u64,
     big_blind_amount: u64,
 ) -> Result<()> {
@@ -28,10 +33,6 @@ pub fn handler<'info>(
         ctx.accounts.backend.key() == table.backend,
         PokerError::NotBackend
     );
-    require!(
-        backend_account == ctx.accounts.backend.key(),
-        PokerError::NotBackend
-    );
     require!(table.current_game.is_none(), PokerError::GameInProgress);
     require!(
         table.player_count >= MIN_PLAYERS,
@@ -39,66 +40,203 @@ pub fn handler<'info>(
     );
 
     // Initialize game state
+    game.version = PokerGame::VERSION;
     game.table = table.key();
     game.game_id = game_id;
     game.stage = GameStage::Waiting;
-    game.player_count = table.player_count;
+    game.variant = table.variant;
 
     // Initialize card state
     game.shuffle_seed = 0;
-    game.card_offset = Euint128::default();
-    game.shuffled_indices = [0, 1, 2, 3, 4];
-    game.deal_cards = [Euint128::default(); 10];
+    game.duplicate_flag = Euint128::default();
+    game.used_cards = Euint128::default();
+    game.batch_used_cards = Euint128::default();
+    let max_seats = table.max_players as usize;
+    game.shuffled_indices = vec![EMPTY_SEAT; max_seats];
+    game.deal_cards = vec![Euint128::default(); max_seats * game.hole_cards_per_player()];
     game.community_cards = [Euint128::default(); 5];
+    game.next_batch = 0;
+    game.batch_pending = false;
+    game.redraw_mask = 0;
     game.cards_processed = false;
+    game.run_twice = false;
+    game.second_board_from = 0;
+    game.second_board = [Euint128::default(); 5];
+    game.public_board = 0;
 
-    // Backend account for off-chain gameplay management
-    game.backend_account = backend_account;
+    // Snapshot the table backend so this game keeps its authority
+    // even if the table backend is rotated mid-hand
+    game.backend_account = ctx.accounts.backend.key();
 
     // Result state
     game.winner_seat = None;
-    game.payouts = [0; 5];
+    game.payouts = vec![0; max_seats];
     game.bump = ctx.bumps.game;
 
-    // Collect blind bets from players via remaining_accounts
-    // Expected: [small_blind_seat, big_blind_seat]
+    // Walk every seat at the table via remaining_accounts, ordered from the
+    // small blind position. The first two dealt-in seats post the blinds;
+    // sitting-out seats passed over before the big blind record the blind
+    // they missed, and returning players post what they owe as dead blinds.
+    require!(
+        ctx.remaining_accounts.len() == table.player_count as usize,
+        PokerError::InvalidSeatAccounts
+    );
+
+    let table_key = table.key();
+    let game_key = game.key();
+    let confidential = table.confidential;
+    let mut seen_mask: u16 = 0;
+    let mut dealt: Vec<Account<PlayerSeat>> = Vec::with_capacity(ctx.remaining_accounts.len());
+
+    for seat_info in ctx.remaining_accounts.iter() {
+        let mut seat: Account<PlayerSeat> = Account::try_from(seat_info)?;
+        require!(seat.version == PlayerSeat::VERSION, PokerError::UnsupportedAccountVersion);
+
+        let expected = Pubkey::create_program_address(
+            &[
+                b"player_seat",
+                table_key.as_ref(),
+                seat.player.as_ref(),
+                &[seat.bump],
+            ],
+            ctx.program_id,
+        )
+        .map_err(|_| PokerError::PlayerNotAtTable)?;
+        require!(expected == *seat_info.key, PokerError::PlayerNotAtTable);
+
+        let seat_bit = 1u16 << seat.seat_index;
+        require!(seen_mask & seat_bit == 0, PokerError::InvalidSeatAccounts);
+        seen_mask |= seat_bit;
+
+        // Clear the previous hand; only dealt-in seats are bound below
+        seat.reset_for_hand(Pubkey::default());
+
+        if seat.sitting_out {
+            seat.straddle_opt_in = false;
+            match dealt.len() {
+                0 => seat.missed_small_blind = true,
+                1 => seat.missed_big_blind = true,
+                _ => {}
+            }
+            seat.exit(&crate::ID)?;
+            continue;
+        }
+
+        // Busted seats wait for a rebuy; confidential stacks are not public,
+        // so there a busted seat is dealt in and settles all-in for nothing
+        // (its forced bets are capped at its empty stack)
+        if !confidential && seat.chips == 0 {
+            seat.straddle_opt_in = false;
+            seat.exit(&crate::ID)?;
+            continue;
+        }
+
+        seat.game = game_key;
+        game.shuffled_indices[dealt.len()] = seat.seat_index;
+        dealt.push(seat);
+    }
+
+    let dealt_in = dealt.len();
+    require!(dealt_in >= MIN_PLAYERS as usize, PokerError::NotEnoughPlayers);
+    game.player_count = dealt_in as u8;
+
+    // ===== POSITIONS =====
+    // Dealt-in seats run from the small blind, so the button is the last
+    // one. A straddle needs a third seat whose player opted in for this
+    // hand; preflop action opens behind a UTG straddle, otherwise after the
+    // big blind (the small blind heads-up).
+    let straddle_pos = match table.straddle {
+        Straddle::Utg if dealt_in >= 3 => Some(2),
+        Straddle::Button if dealt_in >= 3 => Some(dealt_in - 1),
+        _ => None,
+    }
+    .filter(|&pos| dealt[pos].straddle_opt_in);
+    let first_pos = match (table.straddle, straddle_pos) {
+        (Straddle::Utg, Some(pos)) => (pos + 1) % dealt_in,
+        _ => 2 % dealt_in,
+    };
+    game.small_blind_seat = dealt[0].seat_index;
+    game.big_blind_seat = dealt[1].seat_index;
+    game.straddle_seat = straddle_pos.map_or(EMPTY_SEAT, |pos| dealt[pos].seat_index);
+    game.first_to_act = dealt[first_pos].seat_index;
+    game.big_blind = big_blind_amount;
+    // The action clock starts once a transcript is opened
+    game.set_turn(None, 0);
+    game.clock_armed = false;
+    game.started_slot = Clock::get()?.slot;
+
+    // ===== FORCED BETS =====
+    let straddle_amount = big_blind_amount
+        .checked_mul(STRADDLE_MULTIPLIER)
+        .ok_or(PokerError::InvalidBetAmount)?;
     let mut initial_pot: u64 = 0;
 
-    if ctx.remaining_accounts.len() >= 2 {
-        // Small blind (seat index 0 relative to dealer, which is seat 0)
-        let small_blind_seat_info = &ctx.remaining_accounts[0];
-        let mut small_blind_seat: Account<PlayerSeat> = Account::try_from(small_blind_seat_info)?;
-
-        require!(
-            small_blind_seat.chips >= small_blind_amount,
-            PokerError::InsufficientChips
-        );
-        small_blind_seat.chips -= small_blind_amount;
-        initial_pot += small_blind_amount;
-
-        small_blind_seat.exit(&crate::ID)?;
-
-        // Big blind (seat index 1 relative to dealer)
-        let big_blind_seat_info = &ctx.remaining_accounts[1];
-        let mut big_blind_seat: Account<PlayerSeat> = Account::try_from(big_blind_seat_info)?;
-
-        require!(
-            big_blind_seat.chips >= big_blind_amount,
-            PokerError::InsufficientChips
-        );
-        big_blind_seat.chips -= big_blind_amount;
-        initial_pot += big_blind_amount;
-
-        big_blind_seat.exit(&crate::ID)?;
-
-        msg!(
-            "Blinds collected: small={} big={} total={}",
-            small_blind_amount,
-            big_blind_amount,
-            initial_pot
-        );
+    for (pos, seat) in dealt.iter_mut().enumerate() {
+        seat.straddle_opt_in = false;
+        seat.time_bank = TIME_BANK_SLOTS;
+
+        let live_bet = match pos {
+            0 => small_blind_amount,
+            1 => big_blind_amount,
+            _ if Some(pos) == straddle_pos => straddle_amount,
+            _ => 0,
+        };
+
+        // Antes are dead money; a big-blind ante is posted once, by the
+        // big blind, for the whole table
+        let ante = match (table.big_blind_ante, pos) {
+            (false, _) | (true, 1) => table.ante,
+            _ => 0,
+        };
+
+        // Posting a live blind settles anything owed; otherwise owed
+        // blinds go into the pot as dead money
+        let owed = if pos < 2 {
+            0
+        } else {
+            let mut owed = 0;
+            if seat.missed_small_blind {
+                owed += small_blind_amount;
+            }
+            if seat.missed_big_blind {
+                owed += big_blind_amount;
+            }
+            owed
+        };
+
+        // A stack too short for its forced bets is all-in for what it has.
+        // Confidential stacks are encrypted, so there the seat only records
+        // what it owes; settle_game_confidential takes at least that much
+        // (capped at the stack) as the seat's bet for the hand.
+        let dead = ante.checked_add(owed).ok_or(PokerError::InvalidBetAmount)?;
+        if confidential {
+            seat.current_bet = live_bet;
+            seat.total_bet = live_bet.checked_add(dead).ok_or(PokerError::InvalidBetAmount)?;
+        } else {
+            let posted = seat
+                .post_forced_bets(live_bet, dead)
+                .ok_or(PokerError::InvalidBetAmount)?;
+            initial_pot += posted;
+        }
+        seat.missed_small_blind = false;
+        seat.missed_big_blind = false;
+
+        if owed > 0 {
+            msg!("Seat {} posted {} in dead blinds", seat.seat_index, owed);
+        }
+
+        seat.exit(&crate::ID)?;
     }
 
+    msg!(
+        "Forced bets collected: small={} big={} ante={} straddle seat={} total={}",
+        small_blind_amount,
+        big_blind_amount,
+        table.ante,
+        game.straddle_seat,
+        initial_pot
+    );
+
     game.pot = initial_pot;
 
     // Link game to table
@@ -108,7 +246,7 @@ pub fn handler<'info>(
         "Game {} started at table {} with {} players, pot={}",
         game_id,
         table.table_id,
-        table.player_count,
+        game.player_count,
         game.pot
     );
 
@@ -120,14 +258,22 @@ pub fn handler<'info>(
 pub struct StartGame<'info> {
     #[account(
         mut,
-        constraint = table.backend == backend.key() @ PokerError::NotBackend
+        constraint = table.backend == backend.key() @ PokerError::NotBackend,
+        constraint = table.version == PokerTable::VERSION @ PokerError::UnsupportedAccountVersion
     )]
     pub table: Account<'info, PokerTable>,
 
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ PokerError::ProtocolPaused
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
     #[account(
         init,
         payer = backend,
-        space = PokerGame::LEN,
+        space = PokerGame::space(table.max_players, table.variant),
         seeds = [b"game", table.key().as_ref(), &game_id.to_le_bytes()],
         bump
     )]
@@ -0,0 +1,50 @@
+use crate::constants::MAX_PLAYERS;
+use anchor_lang::prelude::*;
+use inco_lightning::types::{Ebool, Euint128};
+
+/// Encrypted hand result of a confidential table awaiting the dispute window
+///
+/// Posted by settle_game_confidential; stacks only change when
+/// finalize_settlement_confidential applies it after deadline_slot.
+/// challenge_settlement_confidential corrects it and reopens the window.
+/// Per-seat arrays are indexed by seat_index.
+#[account]
+pub struct ConfidentialSettlement {
+    /// Account layout version
+    pub version: u8,
+    /// Game this result settles
+    pub game: Pubkey,
+    /// Encrypted total bet over the whole hand per seat, capped at the stack
+    pub total_bets: [Euint128; MAX_PLAYERS as usize],
+    /// Encrypted payout per seat, checked against the pot
+    pub payouts: [Euint128; MAX_PLAYERS as usize],
+    /// Whether the payouts distribute exactly the pot; the hand is void otherwise
+    pub balanced: Ebool,
+    /// Seats dealt into the hand
+    pub dealt_mask: u16,
+    /// Seats the backend reports as folded
+    pub folded_mask: u16,
+    /// Challengers (bit per seat) each seat was already challenged by
+    pub challenged_by: [u16; MAX_PLAYERS as usize],
+    /// Last slot at which the result can be challenged
+    pub deadline_slot: u64,
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl ConfidentialSettlement {
+    /// Current account layout version
+    pub const VERSION: u8 = 1;
+
+    /// 8 (discriminator) + 1 (version) + 32 (game)
+    /// + 160 (total_bets) + 160 (payouts) + 16 (balanced)
+    /// + 2 (dealt_mask) + 2 (folded_mask) + 20 (challenged_by)
+    /// + 8 (deadline_slot) + 1 (bump)
+    pub const LEN: usize = 8 + 1 + 32 + 160 + 160 + 16 + 2 + 2 + 20 + 8 + 1;
+
+    /// Whether a seat was dealt in and still live at showdown
+    pub fn is_live(&self, seat_index: u8) -> bool {
+        let bit = 1u16 << seat_index;
+        self.dealt_mask & bit != 0 && self.folded_mask & bit == 0
+    }
+}
@@ -0,0 +1,94 @@
+use anchor_lang::prelude::*;
+use super::{AccessMode, GameVariant};
+use crate::error::PokerError;
+
+/// Lobby listing for one table
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct LobbyEntry {
+    /// Listed table
+    pub table: Pubkey,
+    /// Small blind amount in lamports
+    pub small_blind: u64,
+    /// Minimum buy-in in lamports
+    pub buy_in_min: u64,
+    /// Maximum buy-in in lamports
+    pub buy_in_max: u64,
+    /// Maximum players allowed
+    pub max_players: u8,
+    /// Number of players currently at table
+    pub player_count: u8,
+    /// Poker variant dealt at the table
+    pub variant: GameVariant,
+    /// Who may join the table
+    pub access_mode: AccessMode,
+}
+
+impl LobbyEntry {
+    /// table (32) + small_blind (8) + buy_in_min (8) + buy_in_max (8)
+    /// + max_players (1) + player_count (1) + variant (1) + access_mode (1)
+    pub const LEN: usize = 32 + 8 + 8 + 8 + 1 + 1 + 1 + 1;
+}
+
+/// One page of the on-chain lobby registry
+/// Lobby UIs read every page instead of scanning all PokerTable accounts
+#[account]
+pub struct LobbyPage {
+    /// Page number (PDA seed)
+    pub page_index: u32,
+    /// Active tables listed on this page
+    pub entries: Vec<LobbyEntry>,
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+}
+
+impl LobbyPage {
+    /// Maximum tables listed per page
+    pub const MAX_ENTRIES: usize = 32;
+
+    /// 8 (discriminator) + 4 (page_index) + 4 + MAX_ENTRIES * LobbyEntry::LEN (entries)
+    /// + 1 (bump)
+    pub const LEN: usize = 8 + 4 + 4 + Self::MAX_ENTRIES * LobbyEntry::LEN + 1;
+
+    /// List a new table on this page
+    pub fn insert(&mut self, entry: LobbyEntry) -> Result<()> {
+        require!(
+            self.entries.len() < Self::MAX_ENTRIES,
+            PokerError::LobbyPageFull
+        );
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    /// Refresh the seat count shown for a listed table
+    pub fn set_player_count(&mut self, table: Pubkey, player_count: u8) -> Result<()> {
+        let entry = self
+            .entries
+            .iter_mut()
+            .find(|e| e.table == table)
+            .ok_or(PokerError::TableNotListed)?;
+        entry.player_count = player_count;
+        Ok(())
+    }
+
+    /// Replace a listed table's entry with fresh settings
+    pub fn update(&mut self, entry: LobbyEntry) -> Result<()> {
+        let listed = self
+            .entries
+            .iter_mut()
+            .find(|e| e.table == entry.table)
+            .ok_or(PokerError::TableNotListed)?;
+        *listed = entry;
+        Ok(())
+    }
+
+    /// Remove a table from this page
+    pub fn remove(&mut self, table: Pubkey) -> Result<()> {
+        let pos = self
+            .entries
+            .iter()
+            .position(|e| e.table == table)
+            .ok_or(PokerError::TableNotListed)?;
+        self.entries.swap_remove(pos);
+        Ok(())
+    }
+}
@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+/// Version reported for accounts written before layouts were versioned
+pub const LEGACY_VERSION: u8 = 0;
+
+/// Layout version of a stored account
+///
+/// Unversioned accounts are recognised by their legacy size; everything
+/// else carries its version in the first byte after the discriminator.
+pub fn stored_version(data: &[u8], legacy_len: usize) -> u8 {
+    if data.len() == legacy_len {
+        LEGACY_VERSION
+    } else {
+        data.get(8).copied().unwrap_or(LEGACY_VERSION)
+    }
+}
+
+/// Resize an account to its new layout and write the upgraded value
+///
+/// The payer tops up rent when the account grows.
+pub fn write_migrated<'info, T: AccountSerialize>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    new_len: usize,
+    value: &T,
+) -> Result<()> {
+    let required = Rent::get()?.minimum_balance(new_len);
+    let current = account.lamports();
+    if required > current {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                system_program::Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            required - current,
+        )?;
+    }
+
+    account.resize(new_len)?;
+    let mut data = account.try_borrow_mut_data()?;
+    let mut writer: &mut [u8] = &mut data[..];
+    value.try_serialize(&mut writer)
+}
@@ -1,23 +1,39 @@
+pub mod confidential_settlement;
+pub mod lobby_page;
+pub mod migration;
 pub mod player_seat;
 pub mod poker_game;
 pub mod poker_table;
+pub mod protocol_config;
+pub mod settlement;
+pub mod table_access;
+pub mod transcript;
 
-pub use player_seat::PlayerSeat;
+pub use confidential_settlement::ConfidentialSettlement;
+pub use lobby_page::{LobbyEntry, LobbyPage};
+pub use player_seat::{PlayerSeat, ShowChoice};
 pub use poker_game::PokerGame;
-pub use poker_table::PokerTable;
+pub use poker_table::{AccessMode, GameVariant, PokerTable, Straddle};
+pub use protocol_config::ProtocolConfig;
+pub use settlement::{OverruledResult, Settlement};
+pub use table_access::{AccessList, TableAccess};
+pub use transcript::{ActionKind, ActionRecord, Transcript};
 
+use crate::constants::MAX_PLAYERS;
 use anchor_lang::prelude::*;
 
 /// Simplified game stages for the new flow
 /// - Waiting: Game created, waiting for cards to be processed
 /// - Playing: Cards processed, gameplay happening off-chain
 /// - Finished: Game settled, winner paid
+/// - PendingSettlement: Result proposed, open to challenges
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
 pub enum GameStage {
     #[default]
     Waiting,
     Playing,
     Finished,
+    PendingSettlement,
 }
 
 /// Round summary for final settlement
@@ -25,12 +41,12 @@ pub enum GameStage {
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
 pub struct RoundSummary {
     pub round_id: u8,
-    pub bets_by_player: [u64; 5],
-    pub folded_mask: u8,
-    pub all_in_mask: u8,
+    pub bets_by_player: [u64; MAX_PLAYERS as usize],
+    pub folded_mask: u16,
+    pub all_in_mask: u16,
     pub pot_delta: u64,
     pub current_bet: u64,
     pub last_raiser: u8,
-    pub acted_mask: u8,
+    pub acted_mask: u16,
     pub action_on: u8,
 }
@@ -1,18 +1,67 @@
+use crate::constants::TIME_BANK_SLOTS;
 use anchor_lang::prelude::*;
 use inco_lightning::types::Euint128;
+use std::ops::Range;
+
+/// What a player chose to show of their hole cards after folding or at
+/// the end of a hand
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ShowChoice {
+    #[default]
+    Undecided,
+    Muck,
+    ShowFirst,
+    ShowSecond,
+    ShowBoth,
+}
+
+impl ShowChoice {
+    /// Whether a seat that chose `self` may now choose `next`
+    ///
+    /// Mucked cards stay mucked and shown cards stay shown; showing one
+    /// card can still be widened to the whole hand.
+    pub fn allows(self, next: ShowChoice) -> bool {
+        match self {
+            ShowChoice::Undecided => true,
+            ShowChoice::ShowFirst | ShowChoice::ShowSecond => {
+                next == self || next == ShowChoice::ShowBoth
+            }
+            ShowChoice::Muck | ShowChoice::ShowBoth => next == self,
+        }
+    }
+
+    /// Positions of the hole cards this choice shows, out of `hole_cards`
+    ///
+    /// ShowBoth shows the whole hand, all four cards in Omaha.
+    pub fn shown_cards(self, hole_cards: usize) -> Range<usize> {
+        match self {
+            ShowChoice::ShowFirst => 0..1,
+            ShowChoice::ShowSecond => 1..2,
+            ShowChoice::ShowBoth => 0..hole_cards,
+            ShowChoice::Undecided | ShowChoice::Muck => 0..0,
+        }
+    }
+}
 
 /// Player seat in an active game
 /// Each player has their own seat account with encrypted hole cards
 #[account]
 pub struct PlayerSeat {
+    /// Account layout version
+    pub version: u8,
     /// Reference to the game
     pub game: Pubkey,
     /// Player's wallet address
     pub player: Pubkey,
-    /// Seat index (0-4)
+    /// Seat index (0 to max_players - 1)
     pub seat_index: u8,
     /// Player's current chip count in lamports
+    ///
+    /// On confidential tables this only holds deposits not yet moved into
+    /// enc_chips.
     pub chips: u64,
+    /// Encrypted stack on confidential tables
+    pub enc_chips: Euint128,
     /// First hole card (encrypted)
     pub hole_card_1: Euint128,
     /// Second hole card (encrypted)
@@ -29,13 +78,175 @@ pub struct PlayerSeat {
     pub has_acted: bool,
     /// Hand rank for showdown (0 = not submitted, higher = better)
     pub hand_rank: u64,
+    /// Whether player is sitting out (skipped for dealing and blinds)
+    pub sitting_out: bool,
+    /// Small blind passed while sitting out, owed as a dead blind
+    pub missed_small_blind: bool,
+    /// Big blind passed while sitting out, owed as a dead blind
+    pub missed_big_blind: bool,
+    /// Show or muck choice for the current hand
+    pub show_choice: ShowChoice,
+    /// Slots of extra thinking time left this hand, spent by timeout_action
+    /// once a turn's deadline passes (0 for none); refilled by start_game
+    pub time_bank: u64,
+    /// Whether the player straddles the next hand if it puts them in the
+    /// table's straddle position; cleared by every start_game
+    pub straddle_opt_in: bool,
+    /// Whether the player accepts the on-chain action clock on their turns
+    /// even when the backend has not started it; kept across hands
+    pub clock_opt_in: bool,
     /// Bump seed for PDA
     pub bump: u8,
 }
 
 impl PlayerSeat {
-    /// 8 (discriminator) + 32 (game) + 32 (player) + 1 (seat_index) + 8 (chips)
-    /// + 16 (hole_card_1) + 16 (hole_card_2) + 8 (current_bet) + 8 (total_bet)
-    /// + 1 (is_folded) + 1 (is_all_in) + 1 (has_acted) + 8 (hand_rank) + 1 (bump)
-    pub const LEN: usize = 8 + 32 + 32 + 1 + 8 + 16 + 16 + 8 + 8 + 1 + 1 + 1 + 8 + 1;
+    /// Current account layout version
+    pub const VERSION: u8 = 1;
+
+    /// 8 (discriminator) + 1 (version) + 32 (game) + 32 (player) + 1 (seat_index) + 8 (chips)
+    /// + 16 (enc_chips) + 16 (hole_card_1) + 16 (hole_card_2) + 8 (current_bet) + 8 (total_bet)
+    /// + 1 (is_folded) + 1 (is_all_in) + 1 (has_acted) + 8 (hand_rank)
+    /// + 1 (sitting_out) + 1 (missed_small_blind) + 1 (missed_big_blind)
+    /// + 1 (show_choice) + 8 (time_bank) + 1 (straddle_opt_in) + 1 (clock_opt_in) + 1 (bump)
+    pub const LEN: usize = 8 + 1 + 32 + 32 + 1 + 8 + 16 + 16 + 16 + 8 + 8 + 1 + 1 + 1 + 8 + 1 + 1 + 1 + 1 + 8 + 1 + 1 + 1;
+
+    /// Size of unversioned (version 0) seat accounts, see PlayerSeatV0
+    pub const LEGACY_LEN: usize = 8 + 32 + 32 + 1 + 8 + 16 + 16 + 8 + 8 + 1 + 1 + 1 + 8 + 1;
+
+    /// Clear per-hand betting state and bind the seat to a game
+    pub fn reset_for_hand(&mut self, game: Pubkey) {
+        self.game = game;
+        self.current_bet = 0;
+        self.total_bet = 0;
+        self.is_folded = false;
+        self.is_all_in = false;
+        self.has_acted = false;
+        self.hand_rank = 0;
+        self.show_choice = ShowChoice::Undecided;
+    }
+
+    /// Post a forced `live_bet` plus `dead` money (antes, dead blinds)
+    ///
+    /// A stack too short for both is all-in for what it has: dead money
+    /// first, then as much of the live bet as is left. Returns the chips
+    /// posted.
+    pub fn post_forced_bets(&mut self, live_bet: u64, dead: u64) -> Option<u64> {
+        let posted = live_bet.checked_add(dead)?.min(self.chips);
+        self.chips -= posted;
+        self.current_bet = posted.saturating_sub(dead);
+        self.total_bet = posted;
+        self.is_all_in = self.chips == 0;
+        Some(posted)
+    }
+
+    /// Chips owed back when a hand is abandoned: the stack plus everything
+    /// the hand took from it, forced bets included
+    pub fn refund_amount(&self) -> Option<u64> {
+        self.chips.checked_add(self.total_bet)
+    }
+}
+
+/// Unversioned seat layout, read by migrate_seat
+#[derive(AnchorDeserialize)]
+pub struct PlayerSeatV0 {
+    pub game: Pubkey,
+    pub player: Pubkey,
+    pub seat_index: u8,
+    pub chips: u64,
+    pub hole_card_1: Euint128,
+    pub hole_card_2: Euint128,
+    pub current_bet: u64,
+    pub total_bet: u64,
+    pub is_folded: bool,
+    pub is_all_in: bool,
+    pub has_acted: bool,
+    pub hand_rank: u64,
+    pub bump: u8,
+}
+
+impl From<PlayerSeatV0> for PlayerSeat {
+    /// Seats already at the table get the same time bank as new ones and
+    /// are not timed on-chain until they opt in
+    fn from(v0: PlayerSeatV0) -> Self {
+        Self {
+            version: PlayerSeat::VERSION,
+            game: v0.game,
+            player: v0.player,
+            seat_index: v0.seat_index,
+            chips: v0.chips,
+            enc_chips: Euint128::default(),
+            hole_card_1: v0.hole_card_1,
+            hole_card_2: v0.hole_card_2,
+            current_bet: v0.current_bet,
+            total_bet: v0.total_bet,
+            is_folded: v0.is_folded,
+            is_all_in: v0.is_all_in,
+            has_acted: v0.has_acted,
+            hand_rank: v0.hand_rank,
+            sitting_out: false,
+            missed_small_blind: false,
+            missed_big_blind: false,
+            show_choice: ShowChoice::Undecided,
+            time_bank: TIME_BANK_SLOTS,
+            straddle_opt_in: false,
+            clock_opt_in: false,
+            bump: v0.bump,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seat(chips: u64) -> PlayerSeat {
+        PlayerSeat {
+            version: PlayerSeat::VERSION,
+            game: Pubkey::default(),
+            player: Pubkey::default(),
+            seat_index: 0,
+            chips,
+            enc_chips: Euint128::default(),
+            hole_card_1: Euint128::default(),
+            hole_card_2: Euint128::default(),
+            current_bet: 0,
+            total_bet: 0,
+            is_folded: false,
+            is_all_in: false,
+            has_acted: false,
+            hand_rank: 0,
+            sitting_out: false,
+            missed_small_blind: false,
+            missed_big_blind: false,
+            show_choice: ShowChoice::Undecided,
+            time_bank: 0,
+            straddle_opt_in: false,
+            clock_opt_in: false,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn forced_bets_come_back_in_a_refund() {
+        // Big blind of 10 plus an ante and a dead small blind
+        let mut big_blind = seat(1000);
+        assert_eq!(big_blind.post_forced_bets(10, 2 + 5), Some(17));
+        assert_eq!((big_blind.chips, big_blind.current_bet), (983, 10));
+        assert_eq!(big_blind.refund_amount(), Some(1000));
+    }
+
+    #[test]
+    fn short_stack_posts_dead_money_first() {
+        let mut short = seat(8);
+        assert_eq!(short.post_forced_bets(10, 5), Some(8));
+        assert_eq!((short.chips, short.current_bet, short.total_bet), (0, 3, 8));
+        assert!(short.is_all_in);
+        assert_eq!(short.refund_amount(), Some(8));
+
+        // Too short to cover even the dead money
+        let mut shorter = seat(4);
+        assert_eq!(shorter.post_forced_bets(10, 5), Some(4));
+        assert_eq!(shorter.current_bet, 0);
+        assert_eq!(shorter.refund_amount(), Some(4));
+    }
 }
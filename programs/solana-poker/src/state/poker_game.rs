@@ -1,13 +1,21 @@
-use super::GameStage;
+use super::{GameStage, GameVariant};
+use crate::constants::{ACTION_TIMEOUT_SLOTS, COMMUNITY_CARDS, EMPTY_SEAT, HOLE_CARDS_PER_PLAYER};
 use anchor_lang::prelude::*;
 use inco_lightning::types::Euint128;
+use std::ops::Range;
 
 /// Simplified poker game state for the new flow
-/// - Cards are processed on-chain (shuffle + deal with encryption)
+/// - Cards are drawn on-chain with Inco e_rand (nobody knows plaintexts)
 /// - Gameplay happens off-chain (backend manages stages)
 /// - Settlement happens on-chain (winner gets pot)
+///
+/// Per-seat vectors are sized from the table's max_players (and deal_cards
+/// also from the variant's hole cards) when the game is created, so 6-max,
+/// full-ring and Omaha tables share the same layout.
 #[account]
 pub struct PokerGame {
+    /// Account layout version
+    pub version: u8,
     /// Reference to the parent table
     pub table: Pubkey,
     /// Game ID (incremented per new game)
@@ -18,20 +26,69 @@ pub struct PokerGame {
     pub pot: u64,
     /// Total number of players in game
     pub player_count: u8,
+    /// Variant dealt, snapshotted from the table
+    pub variant: GameVariant,
+
+    // ===== POSITIONS =====
+    /// Seat that posted the small blind
+    pub small_blind_seat: u8,
+    /// Seat that posted the big blind
+    pub big_blind_seat: u8,
+    /// Seat that posted the straddle (EMPTY_SEAT if none)
+    pub straddle_seat: u8,
+    /// Seat that opens preflop action
+    pub first_to_act: u8,
+    /// Big blind of the hand; the amount to call preflop even when the big
+    /// blind seat was too short to post it in full
+    pub big_blind: u64,
+
+    // ===== ACTION CLOCK =====
+    /// Seat whose action the transcript awaits (EMPTY_SEAT if none)
+    pub to_act: u8,
+    /// Slot after which timeout_action may act for to_act
+    pub action_deadline: u64,
+    /// Whether the backend started the action clock for every seat (see
+    /// arm_action_clock); otherwise only seats that opted in are timed
+    pub clock_armed: bool,
+    /// Slot the hand was started in
+    pub started_slot: u64,
 
     // ===== CARD STATE =====
     /// Shuffle seed derived from blockhash (used for Fisher-Yates shuffle)
     pub shuffle_seed: u64,
-    /// Encrypted offset (blockhash % 52, encrypted) applied to all cards
-    pub card_offset: Euint128,
-    /// Shuffled indices [0,1,2,3,4] in random order for player assignment
-    pub shuffled_indices: [u8; 5],
-    /// Deal cards (encrypted hole cards) - 10 cards, 2 per player
-    pub deal_cards: [Euint128; 10],
+    /// Encrypted flag of the last drawn batch: bit i set when the batch's
+    /// i-th card repeats an earlier one and must be redrawn
+    pub duplicate_flag: Euint128,
+    /// Encrypted bitmask (bit c for card c) of the cards of confirmed batches
+    pub used_cards: Euint128,
+    /// used_cards plus the cards of the batch awaiting confirmation
+    pub batch_used_cards: Euint128,
+    /// Seat index owning each hand of hole cards, shuffled at batch 0
+    /// (first player_count entries are dealt-in seats, the rest EMPTY_SEAT)
+    pub shuffled_indices: Vec<u8>,
+    /// Deal cards (encrypted hole cards) - k per seat (2, or 4 in Omaha),
+    /// hand h at [hk, hk + k)
+    pub deal_cards: Vec<Euint128>,
     /// Community cards (encrypted) - 5 cards total
     pub community_cards: [Euint128; 5],
-    /// Whether all 8 batches of cards have been processed
+    /// Next card batch expected by process_cards_batch
+    pub next_batch: u8,
+    /// Whether next_batch is drawn and awaits confirm_cards_batch
+    pub batch_pending: bool,
+    /// Cards of next_batch (bit i for its i-th card) that repeated and are
+    /// drawn again, the others kept; 0 draws the whole batch
+    pub redraw_mask: u8,
+    /// Whether all card batches have been processed
     pub cards_processed: bool,
+    /// Whether the all-in players agreed to run the board twice
+    pub run_twice: bool,
+    /// Community cards already revealed when the run-twice was agreed;
+    /// the second board shares these and redraws the rest
+    pub second_board_from: u8,
+    /// Second board (encrypted), dealt after a run-twice agreement
+    pub second_board: [Euint128; 5],
+    /// Community cards already opened to SPECTATOR_VIEWER
+    pub public_board: u8,
 
     // ===== ACCESS CONTROL =====
     /// Backend account that can decrypt all community cards
@@ -40,36 +97,240 @@ pub struct PokerGame {
     // ===== GAME RESULT =====
     /// Winner seat index (set during settlement)
     pub winner_seat: Option<u8>,
-    /// Final pot distribution amounts per player
-    pub payouts: [u64; 5],
+    /// Final pot distribution amounts per seat index
+    pub payouts: Vec<u64>,
     /// Bump seed for PDA
     pub bump: u8,
 }
 
 impl PokerGame {
-    /// Calculate space needed for account
-    /// 8 (discriminator) + 32 (table) + 8 (game_id) + 1 (stage)
-    /// + 8 (pot) + 1 (player_count)
-    /// + 8 (shuffle_seed) + 16 (card_offset) + 5 (shuffled_indices)
-    /// + 160 (deal_cards) + 80 (community_cards) + 1 (cards_processed)
+    /// Current account layout version
+    pub const VERSION: u8 = 1;
+
+    /// Calculate space needed for a game of `variant` at a table with
+    /// `max_players` seats, dealing k hole cards per seat
+    /// 8 (discriminator) + 1 (version) + 32 (table) + 8 (game_id) + 1 (stage)
+    /// + 8 (pot) + 1 (player_count) + 1 (variant)
+    /// + 4 (small_blind_seat, big_blind_seat, straddle_seat, first_to_act)
+    /// + 8 (big_blind)
+    /// + 1 (to_act) + 8 (action_deadline) + 1 (clock_armed) + 8 (started_slot)
+    /// + 8 (shuffle_seed) + 16 (duplicate_flag) + 32 (used_cards, batch_used_cards)
+    /// + 4 + n (shuffled_indices)
+    /// + 4 + 16 * kn (deal_cards) + 80 (community_cards)
+    /// + 1 (next_batch) + 1 (batch_pending) + 1 (redraw_mask) + 1 (cards_processed)
+    /// + 1 (run_twice) + 1 (second_board_from) + 80 (second_board)
+    /// + 1 (public_board)
     /// + 32 (backend_account)
-    /// + 2 (winner_seat) + 40 (payouts) + 1 (bump)
-    pub const LEN: usize = 8 + 32 + 8 + 1 + 8 + 1 + 8 + 16 + 5 + 160 + 80 + 1 + 32 + 2 + 40 + 1;
+    /// + 2 (winner_seat) + 4 + 8n (payouts) + 1 (bump)
+    pub fn space(max_players: u8, variant: GameVariant) -> usize {
+        let n = max_players as usize;
+        let k = variant.hole_cards() as usize;
+        8 + 1 + 32 + 8 + 1 + 8 + 1 + 1
+            + 4
+            + 8
+            + 1 + 8 + 1 + 8
+            + 8 + 16 + 32 + (4 + n)
+            + (4 + 16 * k * n) + 80
+            + 1 + 1 + 1 + 1
+            + 1 + 1 + 80
+            + 1
+            + 32
+            + 2 + (4 + 8 * n) + 1
+    }
+
+    /// Size of unversioned (version 0) game accounts, see PokerGameV0
+    pub const LEGACY_LEN: usize = 8 + 32 + 8 + 1 + 8 + 1 + 8 + 16 + 5 + 160 + 80 + 1 + 32 + 2 + 40 + 1;
+
+    /// Hole cards dealt to each seat
+    pub fn hole_cards_per_player(&self) -> usize {
+        self.variant.hole_cards() as usize
+    }
+
+    /// Number of cards to encrypt: hole cards for dealt-in seats plus the board
+    pub fn total_cards(&self) -> usize {
+        self.hole_cards_per_player() * self.player_count as usize + COMMUNITY_CARDS as usize
+    }
+
+    /// Number of cards the second board redraws (0 unless run twice)
+    pub fn second_board_cards(&self) -> usize {
+        if self.run_twice {
+            COMMUNITY_CARDS as usize - self.second_board_from as usize
+        } else {
+            0
+        }
+    }
+
+    /// Number of 2-card batches dealing the hole cards and the first board
+    pub fn first_board_batches(&self) -> u8 {
+        self.total_cards().div_ceil(2) as u8
+    }
+
+    /// Number of 2-card batches needed to process every card
+    pub fn batch_count(&self) -> u8 {
+        self.first_board_batches() + self.second_board_cards().div_ceil(2) as u8
+    }
+
+    /// Whether card batches may be drawn and confirmed
+    ///
+    /// Cards are dealt before play starts, and again for the second board
+    /// once a run-twice is agreed.
+    pub fn is_dealing(&self) -> bool {
+        !self.cards_processed
+            && (self.stage == GameStage::Waiting
+                || (self.stage == GameStage::Playing && self.run_twice))
+    }
+
+    /// Deal positions drawn by a batch
+    ///
+    /// Positions cover the hole cards, then the board, then the cards the
+    /// second board redraws. Second-board batches start a fresh batch, so
+    /// a confirmed batch is never drawn again.
+    pub fn batch_positions(&self, batch_index: u8) -> Range<usize> {
+        let board_end = self.total_cards();
+        let first = self.first_board_batches();
+        let (start, end) = if batch_index < first {
+            (batch_index as usize * 2, board_end)
+        } else {
+            (
+                board_end + (batch_index - first) as usize * 2,
+                board_end + self.second_board_cards(),
+            )
+        };
+        start..(start + 2).min(end)
+    }
+
+    /// Card handle at a deal position
+    pub fn card_at(&self, position: usize) -> Euint128 {
+        let board_end = self.total_cards();
+        let hole_cards = board_end - COMMUNITY_CARDS as usize;
+        if position < hole_cards {
+            self.deal_cards[position]
+        } else if position < board_end {
+            self.community_cards[position - hole_cards]
+        } else {
+            self.second_board[self.second_board_from as usize + position - board_end]
+        }
+    }
 
-    /// Get hole cards for a specific player (using shuffled assignment)
-    pub fn get_player_hole_cards(&self, player_idx: u8) -> Option<(Euint128, Euint128)> {
-        if player_idx >= 5 {
-            return None;
+    /// Store a drawn card at a deal position
+    pub fn set_card(&mut self, position: usize, card: Euint128) {
+        let board_end = self.total_cards();
+        let hole_cards = board_end - COMMUNITY_CARDS as usize;
+        if position < hole_cards {
+            self.deal_cards[position] = card;
+        } else if position < board_end {
+            self.community_cards[position - hole_cards] = card;
+        } else {
+            self.second_board[self.second_board_from as usize + position - board_end] = card;
         }
+    }
 
-        // Find which position in deal_cards corresponds to this player
-        for (pos, &shuffled_player) in self.shuffled_indices.iter().enumerate() {
-            if shuffled_player == player_idx {
-                let card1_idx = pos * 2;
-                let card2_idx = pos * 2 + 1;
-                return Some((self.deal_cards[card1_idx], self.deal_cards[card2_idx]));
+    /// Start the action clock for `to_act`, or stop it once betting is over
+    pub fn set_turn(&mut self, to_act: Option<u8>, slot: u64) {
+        match to_act {
+            Some(seat) => {
+                self.to_act = seat;
+                self.action_deadline = slot + ACTION_TIMEOUT_SLOTS;
+            }
+            None => {
+                self.to_act = EMPTY_SEAT;
+                self.action_deadline = 0;
             }
         }
-        None
+    }
+
+    /// Position of a seat's hand in deal_cards, counted in hands
+    pub fn hand_index(&self, seat_index: u8) -> Option<usize> {
+        self.shuffled_indices[..self.player_count as usize]
+            .iter()
+            .position(|&s| s == seat_index)
+    }
+
+    /// Get hole cards for a specific seat (using shuffled assignment)
+    pub fn get_player_hole_cards(&self, seat_index: u8) -> Option<&[Euint128]> {
+        let k = self.hole_cards_per_player();
+        let start = self.hand_index(seat_index)? * k;
+        Some(&self.deal_cards[start..start + k])
+    }
+}
+
+/// Unversioned game layout, read by migrate_game
+///
+/// Per-seat arrays were fixed at five seats.
+#[derive(AnchorDeserialize)]
+pub struct PokerGameV0 {
+    pub table: Pubkey,
+    pub game_id: u64,
+    pub stage: GameStage,
+    pub pot: u64,
+    pub player_count: u8,
+    pub shuffle_seed: u64,
+    pub card_offset: Euint128,
+    pub shuffled_indices: [u8; 5],
+    pub deal_cards: [Euint128; 10],
+    pub community_cards: [Euint128; 5],
+    pub cards_processed: bool,
+    pub backend_account: Pubkey,
+    pub winner_seat: Option<u8>,
+    pub payouts: [u64; 5],
+    pub bump: u8,
+}
+
+impl PokerGameV0 {
+    /// Upgrade to the current layout, resizing the per-seat arrays to the
+    /// table's max_players
+    ///
+    /// Hands past player_count were never dealt and are marked EMPTY_SEAT.
+    /// Batches and positions were not tracked, so an unfinished deal
+    /// restarts at batch 0 and the used-card mask is rebuilt by the next
+    /// process_cards_batch. Every unversioned game was hold'em, and the
+    /// action clock starts when a transcript is next opened or advanced.
+    /// Transcripts fall back to the big blind seat's posted bet.
+    pub fn into_current(self, max_players: u8) -> PokerGame {
+        let n = (max_players as usize).min(self.shuffled_indices.len());
+        let shuffled_indices = self
+            .shuffled_indices
+            .iter()
+            .enumerate()
+            .take(n)
+            .map(|(pos, &seat)| if pos < self.player_count as usize { seat } else { EMPTY_SEAT })
+            .collect();
+        PokerGame {
+            version: PokerGame::VERSION,
+            table: self.table,
+            game_id: self.game_id,
+            stage: self.stage,
+            pot: self.pot,
+            player_count: self.player_count,
+            variant: GameVariant::Holdem,
+            small_blind_seat: EMPTY_SEAT,
+            big_blind_seat: EMPTY_SEAT,
+            straddle_seat: EMPTY_SEAT,
+            first_to_act: EMPTY_SEAT,
+            big_blind: 0,
+            to_act: EMPTY_SEAT,
+            action_deadline: 0,
+            clock_armed: false,
+            started_slot: 0,
+            shuffle_seed: self.shuffle_seed,
+            duplicate_flag: Euint128::default(),
+            used_cards: Euint128::default(),
+            batch_used_cards: Euint128::default(),
+            shuffled_indices,
+            deal_cards: self.deal_cards[..HOLE_CARDS_PER_PLAYER as usize * n].to_vec(),
+            community_cards: self.community_cards,
+            next_batch: 0,
+            batch_pending: false,
+            redraw_mask: 0,
+            cards_processed: self.cards_processed,
+            run_twice: false,
+            second_board_from: 0,
+            second_board: [Euint128::default(); 5],
+            public_board: 0,
+            backend_account: self.backend_account,
+            winner_seat: self.winner_seat,
+            payouts: self.payouts[..n].to_vec(),
+            bump: self.bump,
+        }
     }
 }
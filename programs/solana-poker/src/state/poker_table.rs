@@ -1,16 +1,99 @@
+use super::LobbyEntry;
+use crate::constants::{
+    DECK_CARDS, HOLE_CARDS_PER_PLAYER, MAX_PLAYERS, OMAHA_HOLE_CARDS, SHORT_DECK_CARDS,
+};
 use anchor_lang::prelude::*;
 
+/// Poker variant dealt at the table
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum GameVariant {
+    /// No-limit Texas hold'em
+    #[default]
+    Holdem,
+    /// Pot-limit Omaha: four hole cards, exactly two of them play
+    Omaha,
+    /// Short-deck (6+) hold'em: 36 cards, a flush beats a full house
+    ShortDeck,
+}
+
+impl GameVariant {
+    /// Hole cards dealt to each seat
+    pub fn hole_cards(self) -> u8 {
+        match self {
+            GameVariant::Holdem | GameVariant::ShortDeck => HOLE_CARDS_PER_PLAYER,
+            GameVariant::Omaha => OMAHA_HOLE_CARDS,
+        }
+    }
+
+    /// Cards in the deck dealt from
+    pub fn deck_size(self) -> u8 {
+        match self {
+            GameVariant::ShortDeck => SHORT_DECK_CARDS,
+            GameVariant::Holdem | GameVariant::Omaha => DECK_CARDS,
+        }
+    }
+
+    /// Largest table the variant may be dealt at
+    ///
+    /// Keeps a hand, second board included, to at most 42 of 52 cards
+    /// (Omaha) or 26 of 36 (short-deck), so redrawing a repeated card
+    /// stays cheap.
+    pub fn max_players(self) -> u8 {
+        match self {
+            GameVariant::Holdem => MAX_PLAYERS,
+            GameVariant::Omaha => 8,
+            GameVariant::ShortDeck => 8,
+        }
+    }
+
+    /// Whether raises are capped at the size of the pot
+    pub fn is_pot_limit(self) -> bool {
+        self == GameVariant::Omaha
+    }
+}
+
+/// Straddle position offered at every hand, if any
+///
+/// The seat in that position straddles a hand only after opting in with
+/// set_straddle.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Straddle {
+    #[default]
+    None,
+    /// First seat after the big blind; action opens behind it
+    Utg,
+    /// Button; action still opens after the big blind
+    Button,
+}
+
+/// Who may join a table (see join_table)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum AccessMode {
+    /// Any wallet
+    #[default]
+    Public,
+    /// Only the creator and allowlisted wallets
+    Allowlist,
+    /// The creator, allowlisted wallets and wallets the creator signed an
+    /// invite for (see TableAccess::invite_digest)
+    Invite,
+}
+
 /// Poker table configuration account
 /// Stores table settings and current game reference
 #[account]
 pub struct PokerTable {
+    /// Account layout version
+    pub version: u8,
     /// Player who created the table (for UI display)
     pub creator: Pubkey,
     /// Backend authority for game operations
     pub backend: Pubkey,
+    /// Backend proposed by the creator, pending acceptance
+    pub pending_backend: Option<Pubkey>,
     /// Unique table identifier
     pub table_id: u64,
-    /// Maximum players allowed (2-5)
+    /// Maximum players allowed (2-10)
     pub max_players: u8,
     /// Minimum buy-in in lamports
     pub buy_in_min: u64,
@@ -18,17 +101,108 @@ pub struct PokerTable {
     pub buy_in_max: u64,
     /// Small blind amount in lamports
     pub small_blind: u64,
+    /// Ante in lamports (0 for none)
+    pub ante: u64,
+    /// Whether the big blind posts a single ante for the whole table
+    pub big_blind_ante: bool,
+    /// Straddle position offered at every hand
+    pub straddle: Straddle,
+    /// Poker variant dealt at every hand
+    pub variant: GameVariant,
     /// Current active game (if any)
     pub current_game: Option<Pubkey>,
     /// Number of players currently at table
     pub player_count: u8,
+    /// Bitmask of occupied seat indices
+    pub occupied_seats: u16,
+    /// Lobby page listing this table
+    pub lobby_page: u32,
+    /// Whether stacks and bets are kept encrypted (see settle_game_confidential)
+    pub confidential: bool,
+    /// Who may join; allowlist and bans live in the TableAccess account
+    pub access_mode: AccessMode,
     /// Bump seed for PDA derivation
     pub bump: u8,
 }
 
 impl PokerTable {
-    /// Account discriminator (8) + creator (32) + backend (32) + table_id (8) + max_players (1) 
-    /// + buy_in_min (8) + buy_in_max (8) + small_blind (8) + current_game (1 + 32) 
-    /// + player_count (1) + bump (1)
-    pub const LEN: usize = 8 + 32 + 32 + 8 + 1 + 8 + 8 + 8 + 33 + 1 + 1;
+    /// Current account layout version
+    pub const VERSION: u8 = 1;
+
+    /// Account discriminator (8) + version (1) + creator (32) + backend (32) + pending_backend (1 + 32)
+    /// + table_id (8) + max_players (1) + buy_in_min (8) + buy_in_max (8) + small_blind (8)
+    /// + ante (8) + big_blind_ante (1) + straddle (1) + variant (1) + current_game (1 + 32) + player_count (1) + occupied_seats (2) + lobby_page (4)
+    /// + confidential (1) + access_mode (1) + bump (1)
+    pub const LEN: usize = 8 + 1 + 32 + 32 + 33 + 8 + 1 + 8 + 8 + 8 + 8 + 1 + 1 + 1 + 33 + 1 + 2 + 4 + 1 + 1 + 1;
+
+    /// Size of unversioned (version 0) table accounts, see PokerTableV0
+    pub const LEGACY_LEN: usize = 8 + 32 + 32 + 8 + 1 + 8 + 8 + 8 + 33 + 1 + 1;
+
+    /// Lobby listing reflecting the table's current settings
+    pub fn lobby_entry(&self, table: Pubkey) -> LobbyEntry {
+        LobbyEntry {
+            table,
+            small_blind: self.small_blind,
+            buy_in_min: self.buy_in_min,
+            buy_in_max: self.buy_in_max,
+            max_players: self.max_players,
+            player_count: self.player_count,
+            variant: self.variant,
+            access_mode: self.access_mode,
+        }
+    }
+
+    /// Lowest seat index not currently occupied
+    pub fn first_free_seat(&self) -> Option<u8> {
+        (0..self.max_players).find(|&i| self.occupied_seats & (1 << i) == 0)
+    }
+}
+
+/// Unversioned table layout, read by migrate_table
+///
+/// Tables created before layouts were versioned carry no version byte.
+#[derive(AnchorDeserialize)]
+pub struct PokerTableV0 {
+    pub creator: Pubkey,
+    pub backend: Pubkey,
+    pub table_id: u64,
+    pub max_players: u8,
+    pub buy_in_min: u64,
+    pub buy_in_max: u64,
+    pub small_blind: u64,
+    pub current_game: Option<Pubkey>,
+    pub player_count: u8,
+    pub bump: u8,
+}
+
+impl From<PokerTableV0> for PokerTable {
+    /// Unversioned tables seated players in join order with no way to
+    /// leave, so seats 0..player_count are the occupied ones. They predate
+    /// every table option, so they are public Hold'em tables with no antes
+    /// or straddle. They were never listed; migrate_table lists them and
+    /// sets lobby_page.
+    fn from(v0: PokerTableV0) -> Self {
+        Self {
+            version: PokerTable::VERSION,
+            creator: v0.creator,
+            backend: v0.backend,
+            pending_backend: None,
+            table_id: v0.table_id,
+            max_players: v0.max_players,
+            buy_in_min: v0.buy_in_min,
+            buy_in_max: v0.buy_in_max,
+            small_blind: v0.small_blind,
+            ante: 0,
+            big_blind_ante: false,
+            straddle: Straddle::None,
+            variant: GameVariant::Holdem,
+            current_game: v0.current_game,
+            player_count: v0.player_count,
+            occupied_seats: (1u16 << v0.player_count) - 1,
+            lobby_page: 0,
+            confidential: false,
+            access_mode: AccessMode::Public,
+            bump: v0.bump,
+        }
+    }
 }
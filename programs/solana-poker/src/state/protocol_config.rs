@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+
+/// Program-wide configuration singleton
+/// Holds the protocol admin, fee settings and the emergency pause switch
+#[account]
+pub struct ProtocolConfig {
+    /// Protocol admin allowed to update config and pause
+    pub admin: Pubkey,
+    /// Treasury receiving protocol fees
+    pub treasury: Pubkey,
+    /// Protocol fee in basis points of each pot
+    pub fee_bps: u16,
+    /// Maximum protocol fee per pot in lamports
+    pub fee_cap: u64,
+    /// When set, no new tables, seats or games can be created
+    pub paused: bool,
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+}
+
+impl ProtocolConfig {
+    /// 8 (discriminator) + 32 (admin) + 32 (treasury) + 2 (fee_bps) + 8 (fee_cap)
+    /// + 1 (paused) + 1 (bump)
+    pub const LEN: usize = 8 + 32 + 32 + 2 + 8 + 1 + 1;
+}
@@ -0,0 +1,309 @@
+use crate::constants::{COMMUNITY_CARDS, MAX_PLAYERS};
+use crate::error::PokerError;
+use anchor_lang::prelude::*;
+
+/// Result set aside when adjudicate_transcript overrules a proposal
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct OverruledResult {
+    pub total_bets: [u64; MAX_PLAYERS as usize],
+    pub payouts: [u64; MAX_PLAYERS as usize],
+    pub second_payouts: [u64; MAX_PLAYERS as usize],
+    pub folded_mask: u16,
+}
+
+/// Proposed hand result awaiting the dispute window
+///
+/// Posted by settle_game; nothing moves until finalize_settlement applies it
+/// after deadline_slot. A successful challenge corrects the result and
+/// reopens the window. Per-seat arrays are indexed by seat_index.
+#[account]
+pub struct Settlement {
+    /// Account layout version
+    pub version: u8,
+    /// Game this result settles
+    pub game: Pubkey,
+    /// Total bet over the whole hand per seat
+    pub total_bets: [u64; MAX_PLAYERS as usize],
+    /// Proposed payout per seat, over both boards when the board ran twice
+    pub payouts: [u64; MAX_PLAYERS as usize],
+    /// Part of each payout won on the second board (zero unless run twice)
+    pub second_payouts: [u64; MAX_PLAYERS as usize],
+    /// Seats dealt into the hand
+    pub dealt_mask: u16,
+    /// Seats the backend reports as folded
+    pub folded_mask: u16,
+    /// Whether the hand was voided and payouts are every seat's own bets
+    pub voided: bool,
+    /// Result the transcript overruled, put back if the transcript turns
+    /// out to hold an equivocation
+    pub overruled: Option<OverruledResult>,
+    /// Last slot at which the result can be challenged
+    pub deadline_slot: u64,
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl Settlement {
+    /// Current account layout version
+    pub const VERSION: u8 = 1;
+
+    /// 8 (discriminator) + 1 (version) + 32 (game)
+    /// + 80 (total_bets) + 80 (payouts) + 80 (second_payouts)
+    /// + 2 (dealt_mask) + 2 (folded_mask) + 1 (voided) + 1 + 242 (overruled)
+    /// + 8 (deadline_slot) + 1 (bump)
+    pub const LEN: usize = 8 + 1 + 32 + 80 + 80 + 80 + 2 + 2 + 1 + 1 + 242 + 8 + 1;
+
+    /// Split a pot run twice into its (first board, second board) halves
+    ///
+    /// The odd chip goes to the first board.
+    pub fn run_twice_split(pot: u64) -> (u64, u64) {
+        let second = pot / 2;
+        (pot - second, second)
+    }
+
+    /// Every pot, main pot first, as (bet level, chips)
+    ///
+    /// A pot is contested by the live seats that bet at least its level.
+    /// `total_bets` is indexed by seat_index and `live_mask` marks the
+    /// seats contesting.
+    fn pots(total_bets: &[u64; MAX_PLAYERS as usize], live_mask: u16) -> Vec<(u64, u64)> {
+        let mut levels: Vec<u64> = (0..MAX_PLAYERS as usize)
+            .filter(|&seat| live_mask & (1 << seat) != 0)
+            .map(|seat| total_bets[seat])
+            .collect();
+        levels.sort_unstable();
+        levels.dedup();
+
+        let mut below = 0;
+        levels
+            .into_iter()
+            .map(|level| {
+                let pot: u64 = total_bets
+                    .iter()
+                    .map(|&bet| bet.min(level) - bet.min(below))
+                    .sum();
+                below = level;
+                (level, pot)
+            })
+            .collect()
+    }
+
+    /// Chips the second board pays out when the board ran twice
+    ///
+    /// Every pot, main and side, is split with run_twice_split, so the
+    /// second board pays half of each pot rounded down.
+    pub fn second_board_share(total_bets: &[u64; MAX_PLAYERS as usize], live_mask: u16) -> u64 {
+        Self::pots(total_bets, live_mask)
+            .iter()
+            .map(|&(_, pot)| Self::run_twice_split(pot).1)
+            .sum()
+    }
+
+    /// Whether one board's winnings `won` pay out exactly `shares`, each
+    /// pot's part going to seats contesting it
+    ///
+    /// Pots nest: a seat contesting a side pot contests every pot below it.
+    /// So it is enough that the seats only contesting the pots up to each
+    /// level won no more than those pots hold.
+    fn pays_pots(
+        won: &[u64; MAX_PLAYERS as usize],
+        total_bets: &[u64; MAX_PLAYERS as usize],
+        live_mask: u16,
+        shares: &[(u64, u64)],
+    ) -> bool {
+        let live = |seat: usize| live_mask & (1 << seat) != 0;
+        if (0..MAX_PLAYERS as usize).any(|seat| !live(seat) && won[seat] > 0) {
+            return false;
+        }
+
+        let mut held = 0;
+        for &(level, share) in shares {
+            held += share;
+            let paid: u64 = (0..MAX_PLAYERS as usize)
+                .filter(|&seat| live(seat) && total_bets[seat] <= level)
+                .map(|seat| won[seat])
+                .sum();
+            if paid > held {
+                return false;
+            }
+        }
+        won.iter().sum::<u64>() == held
+    }
+
+    /// Check a result run twice pays each board its half of every pot, to
+    /// seats contesting that pot
+    ///
+    /// `second_payouts` is the part of each payout won on the second board;
+    /// the rest of the payout was won on the first.
+    pub fn check_run_twice(
+        total_bets: &[u64; MAX_PLAYERS as usize],
+        payouts: &[u64; MAX_PLAYERS as usize],
+        second_payouts: &[u64; MAX_PLAYERS as usize],
+        live_mask: u16,
+    ) -> Result<()> {
+        let mut first_payouts = [0; MAX_PLAYERS as usize];
+        for (first, (&payout, &second)) in first_payouts
+            .iter_mut()
+            .zip(payouts.iter().zip(second_payouts.iter()))
+        {
+            *first = payout.checked_sub(second).ok_or(PokerError::InvalidPayouts)?;
+        }
+
+        let pots = Self::pots(total_bets, live_mask);
+        let split = |board: fn((u64, u64)) -> u64| -> Vec<(u64, u64)> {
+            pots.iter()
+                .map(|&(level, pot)| (level, board(Self::run_twice_split(pot))))
+                .collect()
+        };
+        require!(
+            Self::pays_pots(&first_payouts, total_bets, live_mask, &split(|(first, _)| first))
+                && Self::pays_pots(second_payouts, total_bets, live_mask, &split(|(_, second)| second)),
+            PokerError::InvalidPayouts
+        );
+        Ok(())
+    }
+
+    /// Amount the seat won on the first and on the second board
+    pub fn board_payouts(&self, seat_index: u8) -> (u64, u64) {
+        let idx = seat_index as usize;
+        let second = self.second_payouts[idx];
+        (self.payouts[idx].saturating_sub(second), second)
+    }
+
+    /// Hand what `from` won on one board (0 first, 1 second) to `to`
+    ///
+    /// Returns the chips moved. Every pot `from` won is one `to` contests
+    /// when `to` bet at least as much, so the result stays a legal split.
+    pub fn move_board_payout(&mut self, board_index: usize, from: u8, to: u8) -> u64 {
+        let (first, second) = self.board_payouts(from);
+        let (from, to) = (from as usize, to as usize);
+        let moved = if board_index == 0 { first } else { second };
+        self.payouts[from] -= moved;
+        self.payouts[to] += moved;
+        if board_index == 1 {
+            self.second_payouts[from] -= moved;
+            self.second_payouts[to] += moved;
+        }
+        moved
+    }
+
+    /// Whether a seat was dealt in and still live at showdown
+    pub fn is_live(&self, seat_index: u8) -> bool {
+        let bit = 1u16 << seat_index;
+        self.dealt_mask & bit != 0 && self.folded_mask & bit == 0
+    }
+
+    /// Community cards the proposed result shows were dealt
+    ///
+    /// A showdown between two or more live seats means the whole board was
+    /// dealt; a hand won uncontested or voided proves no street at all.
+    pub fn board_cards_dealt(&self) -> u8 {
+        let live = self.dealt_mask & !self.folded_mask;
+        if !self.voided && live.count_ones() >= 2 {
+            COMMUNITY_CARDS
+        } else {
+            0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settlement(total_bets: &[u64], payouts: &[u64], second_payouts: &[u64]) -> Settlement {
+        let mut settlement = Settlement {
+            version: Settlement::VERSION,
+            game: Pubkey::default(),
+            total_bets: [0; MAX_PLAYERS as usize],
+            payouts: [0; MAX_PLAYERS as usize],
+            second_payouts: [0; MAX_PLAYERS as usize],
+            dealt_mask: (1 << total_bets.len()) - 1,
+            folded_mask: 0,
+            voided: false,
+            overruled: None,
+            deadline_slot: 0,
+            bump: 0,
+        };
+        settlement.total_bets[..total_bets.len()].copy_from_slice(total_bets);
+        settlement.payouts[..payouts.len()].copy_from_slice(payouts);
+        settlement.second_payouts[..second_payouts.len()].copy_from_slice(second_payouts);
+        settlement
+    }
+
+    #[test]
+    fn challenge_moves_only_the_disputed_board() {
+        // Seat 0 scooped both boards of a 200 pot run twice
+        let mut result = settlement(&[100, 100], &[200, 0], &[100, 0]);
+        assert_eq!(result.move_board_payout(1, 0, 1), 100);
+        assert_eq!(result.board_payouts(0), (100, 0));
+        assert_eq!(result.board_payouts(1), (0, 100));
+        let live = result.dealt_mask;
+        assert!(Settlement::check_run_twice(
+            &result.total_bets,
+            &result.payouts,
+            &result.second_payouts,
+            live
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn challenge_moves_the_first_board_of_a_single_run() {
+        let mut result = settlement(&[50, 50, 50], &[150, 0, 0], &[]);
+        assert_eq!(result.move_board_payout(0, 0, 2), 150);
+        assert_eq!(result.payouts[..3], [0, 0, 150]);
+        assert_eq!(result.second_payouts[..3], [0, 0, 0]);
+    }
+
+    #[test]
+    fn only_a_contested_result_proves_the_board_was_dealt() {
+        let mut result = settlement(&[100, 100, 100], &[300, 0, 0], &[]);
+        assert_eq!(result.board_cards_dealt(), COMMUNITY_CARDS);
+
+        result.folded_mask = 0b110;
+        assert_eq!(result.board_cards_dealt(), 0);
+
+        result.folded_mask = 0b100;
+        result.voided = true;
+        assert_eq!(result.board_cards_dealt(), 0);
+    }
+
+    #[test]
+    fn run_twice_pays_each_side_pot_to_its_contestants() {
+        // Seat 0 all-in for 50, seats 1 and 2 in for 150: a 150 main pot
+        // and a 200 side pot seat 0 cannot win
+        let bets = [50, 150, 150];
+        let live = 0b111;
+
+        // Seat 0 scoops the main pot on both boards, seat 1 the side pot
+        let valid = settlement(&bets, &[150, 200, 0], &[75, 100, 0]);
+        assert!(Settlement::check_run_twice(
+            &valid.total_bets,
+            &valid.payouts,
+            &valid.second_payouts,
+            live
+        )
+        .is_ok());
+
+        // Same sums, but the second board's side pot half paid to seat 0
+        let side_pot_to_short = settlement(&bets, &[150, 200, 0], &[150, 25, 0]);
+        assert!(Settlement::check_run_twice(
+            &side_pot_to_short.total_bets,
+            &side_pot_to_short.payouts,
+            &side_pot_to_short.second_payouts,
+            live
+        )
+        .is_err());
+
+        // A folded seat wins nothing on either board
+        let to_folded = settlement(&bets, &[150, 100, 100], &[75, 50, 50]);
+        assert!(Settlement::check_run_twice(
+            &to_folded.total_bets,
+            &to_folded.payouts,
+            &to_folded.second_payouts,
+            0b011
+        )
+        .is_err());
+    }
+}
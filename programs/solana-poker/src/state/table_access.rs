@@ -0,0 +1,89 @@
+use crate::error::PokerError;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+/// Domain tag prefixed to every invite digest
+pub const INVITE_DOMAIN: &[u8] = b"solana-poker:invite";
+
+/// Which of a table's access lists an entry belongs to
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AccessList {
+    /// Wallets that may join an Allowlist or Invite table
+    Allowed,
+    /// Wallets that may never join, whatever the access mode
+    Banned,
+}
+
+/// Creator-managed allowlist and bans of one table
+///
+/// Companion of PokerTable at [b"table_access", table], created with the
+/// table (or later by init_table_access) and checked by join_table.
+#[account]
+pub struct TableAccess {
+    /// Account layout version
+    pub version: u8,
+    /// Table these lists apply to
+    pub table: Pubkey,
+    /// Allowlisted wallets
+    pub allowed: Vec<Pubkey>,
+    /// Banned wallets
+    pub banned: Vec<Pubkey>,
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl TableAccess {
+    /// Current account layout version
+    pub const VERSION: u8 = 1;
+
+    /// Maximum wallets per list
+    pub const MAX_ENTRIES: usize = 32;
+
+    /// 8 (discriminator) + 1 (version) + 32 (table)
+    /// + 2 * (4 + MAX_ENTRIES * 32) (allowed, banned) + 1 (bump)
+    pub const LEN: usize = 8 + 1 + 32 + 2 * (4 + Self::MAX_ENTRIES * 32) + 1;
+
+    /// Message the creator signs to invite a wallet to a table
+    ///
+    /// sha256(INVITE_DOMAIN || table || player), so an invite only admits
+    /// the wallet it names, and only at the table it was signed for.
+    pub fn invite_digest(table: &Pubkey, player: &Pubkey) -> [u8; 32] {
+        hashv(&[INVITE_DOMAIN, table.as_ref(), player.as_ref()]).to_bytes()
+    }
+
+    fn list_mut(&mut self, list: AccessList) -> &mut Vec<Pubkey> {
+        match list {
+            AccessList::Allowed => &mut self.allowed,
+            AccessList::Banned => &mut self.banned,
+        }
+    }
+
+    /// Add a wallet to a list; adding a listed wallet again is a no-op
+    pub fn add(&mut self, list: AccessList, wallet: Pubkey) -> Result<()> {
+        let entries = self.list_mut(list);
+        if entries.contains(&wallet) {
+            return Ok(());
+        }
+        require!(
+            entries.len() < Self::MAX_ENTRIES,
+            PokerError::AccessListFull
+        );
+        entries.push(wallet);
+        Ok(())
+    }
+
+    /// Remove a wallet from a list, if present
+    pub fn remove(&mut self, list: AccessList, wallet: Pubkey) {
+        self.list_mut(list).retain(|w| *w != wallet);
+    }
+
+    /// Whether a wallet is allowlisted
+    pub fn is_allowed(&self, wallet: &Pubkey) -> bool {
+        self.allowed.contains(wallet)
+    }
+
+    /// Whether a wallet is banned
+    pub fn is_banned(&self, wallet: &Pubkey) -> bool {
+        self.banned.contains(wallet)
+    }
+}
@@ -0,0 +1,484 @@
+use crate::constants::{COMMUNITY_CARDS, MAX_PLAYERS};
+use crate::error::PokerError;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+/// Domain tag prefixed to every signed action
+pub const ACTION_DOMAIN: &[u8] = b"solana-poker:action";
+
+/// Last betting round (river)
+pub const RIVER: u8 = 3;
+
+/// Player action kinds; the discriminant is the signed `kind` byte
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ActionKind {
+    Fold,
+    Check,
+    Call,
+    /// Bet or raise; amount is the seat's new total for the street
+    Raise,
+}
+
+/// One player action as signed off-chain
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ActionRecord {
+    pub seat_index: u8,
+    pub kind: ActionKind,
+    pub amount: u64,
+}
+
+/// On-chain replay of a hand's signed action transcript
+///
+/// Each action is signed by its seat's player over action_digest, which
+/// chains the previous head so actions cannot be dropped or reordered.
+/// Replay enforces turn order, stacks and bet sizing from the blinds
+/// start_game posted. Per-seat arrays are indexed by seat_index.
+#[account]
+pub struct Transcript {
+    /// Account layout version
+    pub version: u8,
+    /// Game being replayed
+    pub game: Pubkey,
+    /// Hash of the last applied action (genesis_head before the first)
+    pub head: [u8; 32],
+    /// Number of actions applied
+    pub sequence: u16,
+    /// Betting round: 0 preflop .. 3 river
+    pub street: u8,
+    /// Player owning each seat
+    pub players: [Pubkey; MAX_PLAYERS as usize],
+    /// Stack at the start of the hand, before blinds
+    pub stacks: [u64; MAX_PLAYERS as usize],
+    /// Chips put in over the whole hand
+    pub committed: [u64; MAX_PLAYERS as usize],
+    /// Chips put in on the current street
+    pub street_bets: [u64; MAX_PLAYERS as usize],
+    /// Amount to match on the current street
+    pub current_bet: u64,
+    /// Minimum raise increment on the current street
+    pub min_raise: u64,
+    /// Big blind amount, the opening min raise of every street
+    pub big_blind: u64,
+    /// Whether raises are capped at the size of the pot (Omaha)
+    pub pot_limit: bool,
+    /// Seats dealt into the hand
+    pub dealt_mask: u16,
+    /// Seats that folded
+    pub folded_mask: u16,
+    /// Seats with no chips left behind
+    pub all_in_mask: u16,
+    /// Seats that acted since the last full raise
+    pub acted_mask: u16,
+    /// Seats proven to have signed two different actions for the same
+    /// turn (see prove_equivocation); the replay is no evidence then
+    pub equivocated_mask: u16,
+    /// Seat expected to act next
+    pub to_act: u8,
+    /// Seat that opens postflop streets
+    pub first_postflop: u8,
+    /// Whether betting is over
+    pub complete: bool,
+    /// Account that paid for the transcript, refunded on close
+    pub payer: Pubkey,
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl Transcript {
+    /// Current account layout version
+    pub const VERSION: u8 = 1;
+
+    /// 8 (discriminator) + 1 (version) + 32 (game) + 32 (head)
+    /// + 2 (sequence) + 1 (street) + 320 (players) + 80 (stacks)
+    /// + 80 (committed) + 80 (street_bets) + 8 (current_bet)
+    /// + 8 (min_raise) + 8 (big_blind) + 1 (pot_limit) + 2 * 5 (masks)
+    /// + 1 (to_act) + 1 (first_postflop) + 1 (complete)
+    /// + 32 (payer) + 1 (bump)
+    pub const LEN: usize = 8 + 1 + 32 + 32 + 2 + 1 + 320 + 80 + 80 + 80 + 8 + 8 + 8 + 1 + 10 + 1 + 1 + 1 + 32 + 1;
+
+    /// Head before the first action of a game
+    pub fn genesis_head(game: &Pubkey, game_id: u64) -> [u8; 32] {
+        hashv(&[ACTION_DOMAIN, game.as_ref(), &game_id.to_le_bytes()]).to_bytes()
+    }
+
+    /// Digest a player signs for an action
+    ///
+    /// sha256(ACTION_DOMAIN || game || game_id (le) || sequence (le) ||
+    /// previous head || seat_index || kind || amount (le))
+    pub fn action_digest(
+        game: &Pubkey,
+        game_id: u64,
+        sequence: u16,
+        prev: &[u8; 32],
+        action: &ActionRecord,
+    ) -> [u8; 32] {
+        hashv(&[
+            ACTION_DOMAIN,
+            game.as_ref(),
+            &game_id.to_le_bytes(),
+            &sequence.to_le_bytes(),
+            prev,
+            &[action.seat_index, action.kind as u8],
+            &action.amount.to_le_bytes(),
+        ])
+        .to_bytes()
+    }
+
+    /// Seats still contesting the pot
+    pub fn live_mask(&self) -> u16 {
+        self.dealt_mask & !self.folded_mask
+    }
+
+    /// Chips `seat` must add to stay in on the current street
+    pub fn to_call(&self, seat: u8) -> u64 {
+        self.current_bet.saturating_sub(self.street_bets[seat as usize])
+    }
+
+    /// Seat whose action is awaited, None once betting is over
+    pub fn next_to_act(&self) -> Option<u8> {
+        (!self.complete).then_some(self.to_act)
+    }
+
+    /// Community cards the betting has reached (0 preflop, 3 on the flop,
+    /// 4 on the turn, 5 on the river or once a contested hand runs out)
+    pub fn board_cards_reached(&self) -> u8 {
+        if self.complete && self.live_mask().count_ones() >= 2 {
+            return COMMUNITY_CARDS;
+        }
+        match self.street {
+            0 => 0,
+            street => street + 2,
+        }
+    }
+
+    /// Community cards shown when betting ended with the live seats all-in
+    ///
+    /// None while betting goes on, once a single seat is left, or when the
+    /// river was reached anyway; at most one live seat may have chips left.
+    pub fn all_in_board(&self) -> Option<u8> {
+        let all_in = self.complete
+            && self.live_mask().count_ones() >= 2
+            && self.active_mask().count_ones() <= 1
+            && self.street < RIVER;
+        all_in.then(|| match self.street {
+            0 => 0,
+            street => street + 2,
+        })
+    }
+
+    /// Seats that can still act
+    fn active_mask(&self) -> u16 {
+        self.live_mask() & !self.all_in_mask
+    }
+
+    /// First seat in `mask` at or clockwise after `from`
+    fn next_in(mask: u16, from: u8) -> Option<u8> {
+        (0..MAX_PLAYERS)
+            .map(|step| (from + step) % MAX_PLAYERS)
+            .find(|&seat| mask & (1 << seat) != 0)
+    }
+
+    /// Apply one action after its signature has been checked
+    pub fn apply(&mut self, action: &ActionRecord) -> Result<()> {
+        require!(!self.complete, PokerError::TranscriptComplete);
+        let seat = action.seat_index;
+        require!(
+            seat < MAX_PLAYERS && seat == self.to_act,
+            PokerError::IllegalAction
+        );
+
+        let idx = seat as usize;
+        let bit = 1u16 << seat;
+        let behind = self.stacks[idx] - self.committed[idx];
+        let owed = self.to_call(seat);
+
+        let put_in = match action.kind {
+            ActionKind::Fold => {
+                self.folded_mask |= bit;
+                0
+            }
+            ActionKind::Check => {
+                require!(owed == 0, PokerError::IllegalAction);
+                0
+            }
+            ActionKind::Call => {
+                require!(owed > 0, PokerError::IllegalAction);
+                owed.min(behind)
+            }
+            ActionKind::Raise => {
+                // Seats that acted since the last full raise may only call
+                // or fold
+                require!(
+                    action.amount > self.current_bet && self.acted_mask & bit == 0,
+                    PokerError::IllegalAction
+                );
+                let put_in = action.amount - self.street_bets[idx];
+                require!(put_in <= behind, PokerError::IllegalAction);
+
+                // Pot limit: at most the pot after calling, on top of the call
+                if self.pot_limit {
+                    let pot: u64 = self.committed.iter().sum();
+                    require!(
+                        action.amount <= self.current_bet + pot + owed,
+                        PokerError::IllegalAction
+                    );
+                }
+
+                // Short all-in raises are allowed but do not reopen the
+                // betting; a short all-in opening bet still lets the seats
+                // that checked raise
+                let increment = action.amount - self.current_bet;
+                if increment >= self.min_raise {
+                    self.min_raise = increment;
+                    self.acted_mask = 0;
+                } else {
+                    require!(put_in == behind, PokerError::IllegalAction);
+                    if self.current_bet == 0 {
+                        self.acted_mask = 0;
+                    }
+                }
+                self.current_bet = action.amount;
+                put_in
+            }
+        };
+
+        self.street_bets[idx] += put_in;
+        self.committed[idx] += put_in;
+        if put_in > 0 && put_in == behind {
+            self.all_in_mask |= bit;
+        }
+        self.acted_mask |= bit;
+
+        self.advance(seat);
+        Ok(())
+    }
+
+    /// Move the turn on, closing streets and the hand as betting ends
+    fn advance(&mut self, actor: u8) {
+        if self.live_mask().count_ones() <= 1 {
+            self.complete = true;
+            return;
+        }
+
+        let active = self.active_mask();
+        let street_closed = (0..MAX_PLAYERS)
+            .filter(|&seat| active & (1 << seat) != 0)
+            .all(|seat| {
+                self.acted_mask & (1 << seat) != 0
+                    && self.street_bets[seat as usize] == self.current_bet
+            });
+
+        if !street_closed {
+            if let Some(next) = Self::next_in(active, actor + 1) {
+                self.to_act = next;
+                return;
+            }
+        }
+
+        // Nobody left to bet against, or the river closed: run out the board
+        if active.count_ones() <= 1 || self.street == RIVER {
+            self.complete = true;
+            return;
+        }
+
+        self.street += 1;
+        self.street_bets = [0; MAX_PLAYERS as usize];
+        self.current_bet = 0;
+        self.min_raise = self.big_blind;
+        self.acted_mask = 0;
+        self.to_act = Self::next_in(active, self.first_postflop).unwrap_or(actor);
+    }
+
+    /// Start replay from the posted forced bets
+    ///
+    /// `seats` holds (seat_index, player, chips, total_bet, current_bet) for
+    /// every dealt-in seat as start_game left it, and the blind seats, big
+    /// blind and first preflop actor are those start_game recorded. The
+    /// largest current_bet (a straddle when there is one) is the amount to
+    /// match, and never less than the big blind, even when the big blind
+    /// seat was all-in for less.
+    pub fn start(
+        &mut self,
+        seats: &[(u8, Pubkey, u64, u64, u64)],
+        small_blind_seat: u8,
+        big_blind_seat: u8,
+        big_blind: u64,
+        first_to_act: u8,
+    ) -> Result<()> {
+        let mut posted_big_blind: Option<u64> = None;
+        for &(seat, player, chips, total_bet, current_bet) in seats {
+            let idx = seat as usize;
+            self.players[idx] = player;
+            self.stacks[idx] = chips + total_bet;
+            self.committed[idx] = total_bet;
+            self.street_bets[idx] = current_bet;
+            self.current_bet = self.current_bet.max(current_bet);
+            self.dealt_mask |= 1 << seat;
+            if chips == 0 {
+                self.all_in_mask |= 1 << seat;
+            }
+            if seat == big_blind_seat {
+                posted_big_blind = Some(current_bet);
+            }
+        }
+
+        let bb_amount = posted_big_blind
+            .ok_or(PokerError::NotEnoughPlayers)?
+            .max(big_blind);
+        require!(
+            small_blind_seat < MAX_PLAYERS
+                && first_to_act < MAX_PLAYERS
+                && self.dealt_mask & (1 << small_blind_seat) != 0,
+            PokerError::NotEnoughPlayers
+        );
+
+        self.big_blind = bb_amount;
+        self.min_raise = bb_amount;
+        self.current_bet = self.current_bet.max(bb_amount);
+        // Heads-up the big blind opens postflop, otherwise the small blind
+        self.first_postflop = if seats.len() == 2 { big_blind_seat } else { small_blind_seat };
+
+        match Self::next_in(self.active_mask(), first_to_act) {
+            Some(first) => self.to_act = first,
+            None => self.complete = true,
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BIG_BLIND: u64 = 10;
+
+    /// Hand with seat 0 on the small blind and seat 1 on the big blind,
+    /// seats 0.. holding `stacks` before the blinds
+    fn hand(stacks: &[u64], pot_limit: bool) -> Transcript {
+        let mut transcript = Transcript {
+            version: Transcript::VERSION,
+            game: Pubkey::default(),
+            head: [0; 32],
+            sequence: 0,
+            street: 0,
+            players: [Pubkey::default(); MAX_PLAYERS as usize],
+            stacks: [0; MAX_PLAYERS as usize],
+            committed: [0; MAX_PLAYERS as usize],
+            street_bets: [0; MAX_PLAYERS as usize],
+            current_bet: 0,
+            min_raise: 0,
+            big_blind: 0,
+            pot_limit,
+            dealt_mask: 0,
+            folded_mask: 0,
+            all_in_mask: 0,
+            acted_mask: 0,
+            equivocated_mask: 0,
+            to_act: 0,
+            first_postflop: 0,
+            complete: false,
+            payer: Pubkey::default(),
+            bump: 0,
+        };
+        let seats: Vec<(u8, Pubkey, u64, u64, u64)> = stacks
+            .iter()
+            .enumerate()
+            .map(|(seat, &stack)| {
+                let blind = match seat {
+                    0 => BIG_BLIND / 2,
+                    1 => BIG_BLIND,
+                    _ => 0,
+                }
+                .min(stack);
+                (seat as u8, Pubkey::default(), stack - blind, blind, blind)
+            })
+            .collect();
+        let first_to_act = if stacks.len() == 2 { 0 } else { 2 };
+        transcript
+            .start(&seats, 0, 1, BIG_BLIND, first_to_act)
+            .unwrap();
+        transcript
+    }
+
+    fn act(
+        transcript: &mut Transcript,
+        seat_index: u8,
+        kind: ActionKind,
+        amount: u64,
+    ) -> Result<()> {
+        transcript.apply(&ActionRecord {
+            seat_index,
+            kind,
+            amount,
+        })
+    }
+
+    #[test]
+    fn raises_must_be_at_least_the_last_full_raise() {
+        let mut transcript = hand(&[1000, 1000, 1000], false);
+
+        assert!(act(&mut transcript, 2, ActionKind::Raise, 15).is_err());
+        act(&mut transcript, 2, ActionKind::Raise, 20).unwrap();
+        assert_eq!(transcript.min_raise, 10);
+
+        assert!(act(&mut transcript, 0, ActionKind::Raise, 25).is_err());
+        act(&mut transcript, 0, ActionKind::Raise, 40).unwrap();
+        assert_eq!(transcript.min_raise, 20);
+
+        assert!(act(&mut transcript, 1, ActionKind::Raise, 50).is_err());
+        act(&mut transcript, 1, ActionKind::Raise, 60).unwrap();
+        assert_eq!(transcript.current_bet, 60);
+    }
+
+    #[test]
+    fn pot_limit_caps_raises_at_the_pot_after_calling() {
+        let mut transcript = hand(&[1000, 1000, 1000], true);
+
+        // Pot of 15 plus the 10 to call, on top of the call
+        assert!(act(&mut transcript, 2, ActionKind::Raise, 36).is_err());
+        act(&mut transcript, 2, ActionKind::Raise, 35).unwrap();
+
+        // Pot of 50 plus the 30 to call, on top of the 35 to match
+        assert!(act(&mut transcript, 0, ActionKind::Raise, 116).is_err());
+        act(&mut transcript, 0, ActionKind::Raise, 115).unwrap();
+    }
+
+    #[test]
+    fn short_all_in_raise_does_not_reopen_betting() {
+        let mut transcript = hand(&[25, 1000, 1000], false);
+
+        act(&mut transcript, 2, ActionKind::Raise, 20).unwrap();
+        // All-in for 5 more than the bet, short of a full raise
+        act(&mut transcript, 0, ActionKind::Raise, 25).unwrap();
+        assert_eq!(transcript.min_raise, 10);
+
+        // The big blind has not acted yet and may still raise
+        let mut reraise = transcript.clone();
+        act(&mut reraise, 1, ActionKind::Raise, 35).unwrap();
+
+        act(&mut transcript, 1, ActionKind::Call, 0).unwrap();
+        assert_eq!(transcript.to_act, 2);
+
+        // The original raiser may only call or fold
+        assert!(act(&mut transcript, 2, ActionKind::Raise, 60).is_err());
+        act(&mut transcript, 2, ActionKind::Call, 0).unwrap();
+        assert_eq!(transcript.street, 1);
+    }
+
+    #[test]
+    fn short_all_in_opening_bet_lets_checkers_raise() {
+        let mut transcript = hand(&[1000, 1000, 1000], false);
+        act(&mut transcript, 2, ActionKind::Call, 0).unwrap();
+        act(&mut transcript, 0, ActionKind::Call, 0).unwrap();
+        act(&mut transcript, 1, ActionKind::Check, 0).unwrap();
+        assert_eq!(transcript.street, 1);
+
+        // Leave seat 2 with 5 behind, then open the flop
+        transcript.stacks[2] = transcript.committed[2] + 5;
+        act(&mut transcript, 0, ActionKind::Check, 0).unwrap();
+        act(&mut transcript, 1, ActionKind::Check, 0).unwrap();
+        act(&mut transcript, 2, ActionKind::Raise, 5).unwrap();
+
+        act(&mut transcript, 0, ActionKind::Raise, 15).unwrap();
+    }
+}
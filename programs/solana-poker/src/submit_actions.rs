@@ -0,0 +1,74 @@
+use crate::ed25519::signers_of;
+use crate::error::PokerError;
+use crate::state::{ActionRecord, PokerGame, Transcript};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+
+/// Replay the next signed actions of a hand
+///
+/// Permissionless. Each action must be signed by its seat's player over
+/// Transcript::action_digest, carried as Ed25519 precompile instructions
+/// earlier in the transaction. Long hands are submitted over several
+/// transactions; the hash chain keeps them in order.
+///
+/// Each submission restarts the action clock for the next seat to act.
+pub fn handler(ctx: Context<SubmitActions>, actions: Vec<ActionRecord>) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let transcript = &mut ctx.accounts.transcript;
+
+    require!(!actions.is_empty(), PokerError::IllegalAction);
+
+    for action in actions.iter() {
+        require!(
+            (action.seat_index as usize) < transcript.players.len(),
+            PokerError::IllegalAction
+        );
+        let digest = Transcript::action_digest(
+            &game.key(),
+            game.game_id,
+            transcript.sequence,
+            &transcript.head,
+            action,
+        );
+
+        let signers = signers_of(&ctx.accounts.instructions, &digest)?;
+        require!(
+            signers.contains(&transcript.players[action.seat_index as usize]),
+            PokerError::MissingPlayerSignature
+        );
+
+        transcript.apply(action)?;
+        transcript.head = digest;
+        transcript.sequence += 1;
+    }
+    game.set_turn(transcript.next_to_act(), Clock::get()?.slot);
+
+    msg!(
+        "Transcript at action {}, street {}, complete={}",
+        transcript.sequence,
+        transcript.street,
+        transcript.complete
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SubmitActions<'info> {
+    #[account(
+        mut,
+        constraint = game.version == PokerGame::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub game: Account<'info, PokerGame>,
+
+    #[account(
+        mut,
+        seeds = [b"transcript", game.key().as_ref()],
+        bump = transcript.bump
+    )]
+    pub transcript: Account<'info, Transcript>,
+
+    /// CHECK: Instructions sysvar holding the players' Ed25519 signatures
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+}
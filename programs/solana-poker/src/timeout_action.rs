@@ -0,0 +1,119 @@
+use crate::constants::EMPTY_SEAT;
+use crate::error::PokerError;
+use crate::state::{ActionKind, ActionRecord, GameStage, PlayerSeat, PokerGame, PokerTable, Transcript};
+use anchor_lang::prelude::*;
+
+/// Act for a player who let their turn's deadline pass
+///
+/// Permissionless crank over the transcript of a hand still being played.
+/// Players may be acting off-chain and not watching the transcript, so a
+/// seat is only timed once the backend started the clock (arm_action_clock)
+/// or the player opted in (set_action_clock). Once the deadline passes,
+/// the seat's time bank for the hand is spent first: the deadline moves
+/// out by the whole bank and the bank is emptied. After that, the seat
+/// checks when checking is free and folds otherwise.
+///
+/// The forced action is chained into the transcript like a signed one
+/// (same Transcript::action_digest), so players keep signing over the new
+/// head.
+pub fn handler(ctx: Context<TimeoutAction>) -> Result<()> {
+    let game = &mut ctx.accounts.game;
+    let transcript = &mut ctx.accounts.transcript;
+    let seat = &mut ctx.accounts.player_seat;
+
+    require!(
+        game.to_act != EMPTY_SEAT && transcript.next_to_act() == Some(game.to_act),
+        PokerError::NoActionPending
+    );
+    require!(seat.game == game.key(), PokerError::SeatNotInGame);
+    require!(
+        game.clock_armed || seat.clock_opt_in,
+        PokerError::ActionClockNotStarted
+    );
+
+    let slot = Clock::get()?.slot;
+    require!(slot > game.action_deadline, PokerError::ActionNotExpired);
+
+    if seat.time_bank > 0 {
+        game.action_deadline += seat.time_bank;
+        seat.time_bank = 0;
+        msg!(
+            "Seat {} time bank in use until slot {}",
+            seat.seat_index,
+            game.action_deadline
+        );
+        if slot <= game.action_deadline {
+            return Ok(());
+        }
+    }
+
+    let kind = if transcript.to_call(seat.seat_index) == 0 {
+        ActionKind::Check
+    } else {
+        ActionKind::Fold
+    };
+    let action = ActionRecord {
+        seat_index: seat.seat_index,
+        kind,
+        amount: 0,
+    };
+
+    let digest = Transcript::action_digest(
+        &game.key(),
+        game.game_id,
+        transcript.sequence,
+        &transcript.head,
+        &action,
+    );
+    transcript.apply(&action)?;
+    transcript.head = digest;
+    transcript.sequence += 1;
+
+    if kind == ActionKind::Fold {
+        seat.is_folded = true;
+    }
+    game.set_turn(transcript.next_to_act(), slot);
+
+    msg!(
+        "Seat {} timed out: {:?}. Transcript at action {}",
+        action.seat_index,
+        kind,
+        transcript.sequence
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct TimeoutAction<'info> {
+    #[account(
+        constraint = table.version == PokerTable::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub table: Account<'info, PokerTable>,
+
+    #[account(
+        mut,
+        constraint = game.table == table.key() @ PokerError::NoActiveGame,
+        constraint = game.stage == GameStage::Playing @ PokerError::InvalidGameStage,
+        constraint = game.version == PokerGame::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub game: Account<'info, PokerGame>,
+
+    #[account(
+        mut,
+        seeds = [b"transcript", game.key().as_ref()],
+        bump = transcript.bump,
+        constraint = transcript.version == Transcript::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub transcript: Account<'info, Transcript>,
+
+    /// Seat of the player who ran out of time
+    #[account(
+        mut,
+        seeds = [b"player_seat", table.key().as_ref(), player_seat.player.as_ref()],
+        bump = player_seat.bump,
+        constraint = player_seat.seat_index == game.to_act @ PokerError::NoActionPending,
+        constraint = player_seat.version == PlayerSeat::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub player_seat: Account<'info, PlayerSeat>,
+}
@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+use crate::constants::MAX_FEE_BPS;
+use crate::error::PokerError;
+use crate::state::ProtocolConfig;
+
+/// Protocol admin updates the admin, treasury and fee settings
+pub fn handler(
+    ctx: Context<UpdateConfig>,
+    new_admin: Pubkey,
+    treasury: Pubkey,
+    fee_bps: u16,
+    fee_cap: u64,
+) -> Result<()> {
+    require!(fee_bps <= MAX_FEE_BPS, PokerError::InvalidFee);
+
+    let config = &mut ctx.accounts.config;
+    config.admin = new_admin;
+    config.treasury = treasury;
+    config.fee_bps = fee_bps;
+    config.fee_cap = fee_cap;
+
+    msg!(
+        "Protocol config updated: admin={} treasury={} fee_bps={} fee_cap={}",
+        new_admin,
+        treasury,
+        fee_bps,
+        fee_cap
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ PokerError::NotAdmin
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    pub admin: Signer<'info>,
+}
@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+use crate::state::{AccessList, PokerTable, TableAccess};
+use crate::error::PokerError;
+
+/// Creator adds a wallet to, or removes it from, the allowlist or bans
+///
+/// Bans only stop future joins; a banned player already seated keeps
+/// their seat until they leave.
+pub fn handler(
+    ctx: Context<UpdateTableAccess>,
+    wallet: Pubkey,
+    list: AccessList,
+    add: bool,
+) -> Result<()> {
+    let table_access = &mut ctx.accounts.table_access;
+    if add {
+        table_access.add(list, wallet)?;
+    } else {
+        table_access.remove(list, wallet);
+    }
+
+    msg!(
+        "Table {} {:?} list: {} {}",
+        ctx.accounts.table.table_id,
+        list,
+        if add { "added" } else { "removed" },
+        wallet
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateTableAccess<'info> {
+    #[account(
+        constraint = table.creator == creator.key() @ PokerError::NotCreator,
+        constraint = table.version == PokerTable::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub table: Account<'info, PokerTable>,
+
+    #[account(
+        mut,
+        seeds = [b"table_access", table.key().as_ref()],
+        bump = table_access.bump,
+        constraint = table_access.version == TableAccess::VERSION @ PokerError::UnsupportedAccountVersion
+    )]
+    pub table_access: Account<'info, TableAccess>,
+
+    pub creator: Signer<'info>,
+}
@@ -1,7 +1,6 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { SolanaPoker } from "../target/types/solana_poker";
-import { encryptValue } from "@inco/solana-sdk/encryption";
 import { decrypt } from "@inco/solana-sdk/attested-decrypt";
 import nacl from "tweetnacl";
 
@@ -21,9 +20,6 @@ describe("solana-poker: Simplified Flow", () => {
   const player4 = anchor.web3.Keypair.generate();
   const player5 = anchor.web3.Keypair.generate();
 
-  // Card values to encrypt (will be offset by blockhash-derived value)
-  const CARD_VALUES = [2, 4, 6, 8, 10, 12, 14, 16, 18, 20, 22, 24, 26, 28, 30];
-
   const tableId = new anchor.BN(Math.floor(Date.now() / 1000));
   const gameId = tableId.add(new anchor.BN(1));
 
@@ -34,8 +30,14 @@ describe("solana-poker: Simplified Flow", () => {
   const bigBlind = new anchor.BN(200_000); // 0.0002 SOL
   const playerBuyIn = new anchor.BN(50_000_000);
 
+  const lobbyPageIndex = tableId.toNumber() % 0xffffffff;
+
+  let configPda: anchor.web3.PublicKey;
+  let incoAuthorityPda: anchor.web3.PublicKey;
+  let lobbyPagePda: anchor.web3.PublicKey;
   let tablePda: anchor.web3.PublicKey;
   let vaultPda: anchor.web3.PublicKey;
+  let tableAccessPda: anchor.web3.PublicKey;
   let gamePda: anchor.web3.PublicKey;
   let adminSeatPda: anchor.web3.PublicKey;
   let player2SeatPda: anchor.web3.PublicKey;
@@ -153,82 +155,27 @@ async function sendAndConfirm(fn: () => Promise<string>, desc: string) {
   }> = [];
   let decryptedCommunityCards: string[] = [];
 
-  let cardOffsetAllowed = false;
-  let lastCardOffsetPlaintext: string | null = null;
   const extractHandle = (h: any) => h;
 
-  const logCardOffset = async (label: string) => {
-    const game = await program.account.pokerGame.fetch(gamePda);
-    const handle = extractHandle(game.cardOffset);
-    const handleString = handleToDecimalString(handle);
-
-    console.log(`card_offset ${label} handle:`, handleString.slice(0, 16));
-
-    if (!cardOffsetAllowed) {
-      const allowance = anchor.web3.PublicKey.findProgramAddressSync(
-        [handleToBytesLE(handle), admin.publicKey.toBuffer()],
-        INCO_LIGHTNING_ID
-      )[0];
-
-      await sendAndConfirm(
-        () =>
-          program.methods
-            .revealCardOffset()
-            .accounts({
-              table: tablePda,
-              game: gamePda,
-              backend: admin.publicKey,
-              player: admin.publicKey,
-              incoLightningProgram: INCO_LIGHTNING_ID,
-              systemProgram: anchor.web3.SystemProgram.programId,
-            })
-            .remainingAccounts([
-              { pubkey: allowance, isWritable: true, isSigner: false },
-            ])
-            .rpc(),
-        "revealCardOffset"
-      );
-
-      cardOffsetAllowed = true;
-    }
+  // Compute all PDAs before tests run
+  before(async () => {
+    [configPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("config")],
+      program.programId
+    );
 
-    const signMessage = getSignMessage();
-    if (!signMessage) {
-      console.log(`card_offset ${label}: signMessage not available`);
-      return;
-    }
+    [incoAuthorityPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("inco_authority")],
+      program.programId
+    );
 
-    try {
-      const result = await decryptWithRetry(
-        [handleToDecimalString(handle)],
-        admin.publicKey,
-        signMessage,
-        `card_offset ${label}`,
-        5,
-        200
-      );
-      const plaintext = result.plaintexts[0];
-      if (lastCardOffsetPlaintext && lastCardOffsetPlaintext !== plaintext) {
-        console.log(
-          `card_offset ${label}: PLAINTEXT CHANGED`,
-          lastCardOffsetPlaintext,
-          "->",
-          plaintext
-        );
-      } else {
-        console.log(`card_offset ${label}:`, plaintext, "(should be 0-51)");
-      }
-      lastCardOffsetPlaintext = plaintext;
-    } catch (err: any) {
-      console.log(
-        `card_offset ${label} decrypt failed:`,
-        err?.message ?? String(err)
-      );
-    }
-  };
+    const pageSeed = Buffer.alloc(4);
+    pageSeed.writeUInt32LE(lobbyPageIndex);
+    [lobbyPagePda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("lobby"), pageSeed],
+      program.programId
+    );
 
-  // Compute all PDAs before tests run
-  before(async () => {
     [tablePda] = anchor.web3.PublicKey.findProgramAddressSync(
       [
         Buffer.from("table"),
@@ -243,6 +190,11 @@ async function sendAndConfirm(fn: () => Promise<string>, desc: string) {
       program.programId
     );
 
+    [tableAccessPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("table_access"), tablePda.toBuffer()],
+      program.programId
+    );
+
     [gamePda] = anchor.web3.PublicKey.findProgramAddressSync(
       [
         Buffer.from("game"),
@@ -306,14 +258,68 @@ async function sendAndConfirm(fn: () => Promise<string>, desc: string) {
   // TEST 1: Setup table and join players
   // ========================================
   it("1. Setup table and join players", async () => {
+    // Protocol config is a singleton; the deployer initializes it once
+    const existingConfig = await program.account.protocolConfig.fetchNullable(
+      configPda
+    );
+    if (!existingConfig) {
+      const [programData] = anchor.web3.PublicKey.findProgramAddressSync(
+        [program.programId.toBuffer()],
+        new anchor.web3.PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+      );
+      await sendAndConfirm(
+        () =>
+          program.methods
+            .initializeConfig(admin.publicKey, 0, new anchor.BN(0))
+            .accounts({
+              config: configPda,
+              program: program.programId,
+              programData,
+              admin: admin.publicKey,
+              systemProgram: anchor.web3.SystemProgram.programId,
+            })
+            .rpc(),
+        "initializeConfig"
+      );
+    }
+
+    // Fresh lobby page for this run
+    await sendAndConfirm(
+      () =>
+        program.methods
+          .initLobbyPage(lobbyPageIndex)
+          .accounts({
+            lobbyPage: lobbyPagePda,
+            payer: admin.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .rpc(),
+      "initLobbyPage"
+    );
+
     // Create table
     await sendAndConfirm(
       () =>
         program.methods
-          .createTable(tableId, maxPlayers, buyInMin, buyInMax, smallBlind, admin.publicKey)
+          .createTable(tableId, {
+            maxPlayers,
+            buyInMin,
+            buyInMax,
+            smallBlind,
+            backendAccount: admin.publicKey,
+            confidential: false,
+            ante: new anchor.BN(0),
+            bigBlindAnte: false,
+            straddle: { none: {} },
+            variant: { holdem: {} },
+            accessMode: { public: {} },
+          })
           .accounts({
             table: tablePda,
+            config: configPda,
             vault: vaultPda,
+            tableAccess: tableAccessPda,
+            lobbyPage: lobbyPagePda,
             creator: admin.publicKey,
             systemProgram: anchor.web3.SystemProgram.programId,
           })
@@ -329,9 +335,13 @@ async function sendAndConfirm(fn: () => Promise<string>, desc: string) {
           .joinTable(playerBuyIn)
           .accounts({
             table: tablePda,
+            config: configPda,
             vault: vaultPda,
+            lobbyPage: lobbyPagePda,
+            tableAccess: tableAccessPda,
             playerSeat: adminSeatPda,
             player: admin.publicKey,
+            instructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
             systemProgram: anchor.web3.SystemProgram.programId,
           })
           .rpc(),
@@ -371,9 +381,13 @@ async function sendAndConfirm(fn: () => Promise<string>, desc: string) {
             .joinTable(playerBuyIn)
             .accounts({
               table: tablePda,
+              config: configPda,
               vault: vaultPda,
+              lobbyPage: lobbyPagePda,
+              tableAccess: tableAccessPda,
               playerSeat: seatPda,
               player: player.publicKey,
+              instructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
               systemProgram: anchor.web3.SystemProgram.programId,
             })
             .signers([player])
@@ -389,21 +403,28 @@ async function sendAndConfirm(fn: () => Promise<string>, desc: string) {
   // TEST 2: Start game with blind bets
   // ========================================
   it("2. Start game with blind bets", async () => {
+    // Every seat, ordered from the small blind position:
     // Small blind = seat 1 (player2), Big blind = seat 2 (player3)
     await sendAndConfirm(
       () =>
         program.methods
-          .startGame(gameId, admin.publicKey, smallBlind, bigBlind)
+          .startGame(gameId, smallBlind, bigBlind)
           .accounts({
             table: tablePda,
+            config: configPda,
             game: gamePda,
-            admin: admin.publicKey,
+            backend: admin.publicKey,
             systemProgram: anchor.web3.SystemProgram.programId,
           })
-          .remainingAccounts([
-            { pubkey: player2SeatPda, isWritable: true, isSigner: false },
-            { pubkey: player3SeatPda, isWritable: true, isSigner: false },
-          ])
+          .remainingAccounts(
+            [
+              player2SeatPda,
+              player3SeatPda,
+              player4SeatPda,
+              player5SeatPda,
+              adminSeatPda,
+            ].map((pubkey) => ({ pubkey, isWritable: true, isSigner: false }))
+          )
           .rpc(),
       "startGame"
     );
@@ -420,34 +441,26 @@ async function sendAndConfirm(fn: () => Promise<string>, desc: string) {
   // TEST 3: Process cards (8 batches)
   // ========================================
   it("3. Process cards (8 batches)", async () => {
-    console.log("Card values to encrypt:", CARD_VALUES);
-
-    const encryptedCards: Buffer[] = [];
-    for (let i = 0; i < 15; i++) {
-      const hex = await encryptValue(BigInt(CARD_VALUES[i]));
-      encryptedCards.push(Buffer.from(hex, "hex"));
-      if (i < 4) {
-        console.log(`  card[${i}] value=${CARD_VALUES[i]} encrypted`);
-      }
+    const signMessage = getSignMessage();
+    if (!signMessage) {
+      throw new Error("signMessage not available to confirm batches");
     }
 
-    for (let batch = 0; batch < 8; batch++) {
-      const idx0 = batch * 2;
-      const idx1 = batch * 2 + 1;
-      const card0 = encryptedCards[idx0] || encryptedCards[0];
-      const card1 =
-        idx1 < 15
-          ? encryptedCards[idx1]
-          : Buffer.from(await encryptValue(0n), "hex");
+    let redraws = 0;
+    for (;;) {
+      const pending = await program.account.pokerGame.fetch(gamePda);
+      if (pending.cardsProcessed) break;
+      const batch = pending.nextBatch;
 
       await sendAndConfirm(
         () =>
           program.methods
-            .processCardsBatch(batch, card0, card1, 0)
+            .processCardsBatch(batch)
             .accounts({
               table: tablePda,
               game: gamePda,
               backend: admin.publicKey,
+              incoAuthority: incoAuthorityPda,
               incoLightningProgram: INCO_LIGHTNING_ID,
               systemProgram: anchor.web3.SystemProgram.programId,
             })
@@ -455,10 +468,66 @@ async function sendAndConfirm(fn: () => Promise<string>, desc: string) {
         `processCardsBatch(${batch})`
       );
 
-      if (batch === 0 || batch === 7) {
-        await logCardOffset(`after batch ${batch}`);
+      // The flag handle only exists now, so derive its allowance afterwards
+      const drawn = await program.account.pokerGame.fetch(gamePda);
+      const flagHandle = extractHandle(drawn.duplicateFlag);
+      const allowance = anchor.web3.PublicKey.findProgramAddressSync(
+        [handleToBytesLE(flagHandle), admin.publicKey.toBuffer()],
+        INCO_LIGHTNING_ID
+      )[0];
+
+      await sendAndConfirm(
+        () =>
+          program.methods
+            .revealDuplicateFlag()
+            .accounts({
+              table: tablePda,
+              game: gamePda,
+              backend: admin.publicKey,
+              incoAuthority: incoAuthorityPda,
+              incoLightningProgram: INCO_LIGHTNING_ID,
+              systemProgram: anchor.web3.SystemProgram.programId,
+            })
+            .remainingAccounts([
+              { pubkey: allowance, isWritable: true, isSigner: false },
+            ])
+            .rpc(),
+        `revealDuplicateFlag(${batch})`
+      );
+
+      const result = await decryptWithRetry(
+        [handleToDecimalString(flagHandle)],
+        admin.publicKey,
+        signMessage,
+        `duplicate_flag batch ${batch}`
+      );
+      const flag = result.plaintexts[0];
+
+      const confirmIx = await program.methods
+        .confirmCardsBatch(new anchor.BN(flag))
+        .accounts({
+          table: tablePda,
+          game: gamePda,
+          backend: admin.publicKey,
+          instructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+          incoLightningProgram: INCO_LIGHTNING_ID,
+        })
+        .instruction();
+      const tx = new anchor.web3.Transaction();
+      result.ed25519Instructions.forEach((ix: any) => tx.add(ix));
+      tx.add(confirmIx);
+
+      await sendAndConfirm(
+        () => provider.sendAndConfirm(tx),
+        `confirmCardsBatch(${batch}) flag=${flag}`
+      );
+
+      if (flag !== "0") {
+        redraws++;
+        console.log(`  batch ${batch} repeated a card, redrawing`);
       }
     }
+    console.log("Redrawn batches:", redraws);
 
     const game = await program.account.pokerGame.fetch(gamePda);
     console.log("\nCards processed:");
@@ -478,30 +547,35 @@ async function sendAndConfirm(fn: () => Promise<string>, desc: string) {
         label: "admin",
         publicKey: admin.publicKey,
         seatPda: adminSeatPda,
+        keypair: undefined as anchor.web3.Keypair | undefined,
         signMessage: getSignMessage(),
       },
       {
         label: "player2",
         publicKey: player2.publicKey,
         seatPda: player2SeatPda,
+        keypair: player2,
         signMessage: getKeypairSignMessage(player2),
       },
       {
         label: "player3",
         publicKey: player3.publicKey,
         seatPda: player3SeatPda,
+        keypair: player3,
         signMessage: getKeypairSignMessage(player3),
       },
       {
         label: "player4",
         publicKey: player4.publicKey,
         seatPda: player4SeatPda,
+        keypair: player4,
         signMessage: getKeypairSignMessage(player4),
       },
       {
         label: "player5",
         publicKey: player5.publicKey,
         seatPda: player5SeatPda,
+        keypair: player5,
         signMessage: getKeypairSignMessage(player5),
       },
     ];
@@ -542,7 +616,7 @@ async function sendAndConfirm(fn: () => Promise<string>, desc: string) {
               game: gamePda,
               playerSeat: player.seatPda,
               player: player.publicKey,
-              backend: admin.publicKey,
+              incoAuthority: incoAuthorityPda,
               incoLightningProgram: INCO_LIGHTNING_ID,
               systemProgram: anchor.web3.SystemProgram.programId,
             })
@@ -550,6 +624,7 @@ async function sendAndConfirm(fn: () => Promise<string>, desc: string) {
               { pubkey: allowance1, isWritable: true, isSigner: false },
               { pubkey: allowance2, isWritable: true, isSigner: false },
             ])
+            .signers(player.keypair ? [player.keypair] : [])
             .rpc(),
         `revealHand(${player.label})`
         
@@ -617,6 +692,7 @@ async function sendAndConfirm(fn: () => Promise<string>, desc: string) {
             table: tablePda,
             game: gamePda,
             backend: admin.publicKey,
+            incoAuthority: incoAuthorityPda,
             incoLightningProgram: INCO_LIGHTNING_ID,
             systemProgram: anchor.web3.SystemProgram.programId,
           })
@@ -670,44 +746,95 @@ async function sendAndConfirm(fn: () => Promise<string>, desc: string) {
   });
 
   // ========================================
-  // TEST 6: Settle game (pay winner)
+  // TEST 6: Settle game (credit winner's seat)
   // ========================================
-  it("6. Settle game and pay winner", async () => {
+  it("6. Settle game and credit winner", async () => {
     // In a real game, backend would determine winner based on hand evaluation
     // For this test, we'll just pick admin (seat 0) as the winner
     const winnerSeatIndex = 0;
 
     // Final pot would include all bets collected during gameplay
-    // For this test, use the initial pot (blinds only)
+    // For this test, nobody bets beyond the blinds
     const game = await program.account.pokerGame.fetch(gamePda);
     const finalPot = game.pot;
 
+    const seatPdas = [
+      adminSeatPda,
+      player2SeatPda,
+      player3SeatPda,
+      player4SeatPda,
+      player5SeatPda,
+    ];
+    const seats = await Promise.all(
+      seatPdas.map((pda) => program.account.playerSeat.fetch(pda))
+    );
+    const totalBets = seats.map((seat) => seat.totalBet);
+    const payouts = seats.map((seat) =>
+      seat.seatIndex === winnerSeatIndex ? finalPot : new anchor.BN(0)
+    );
+
     console.log("\nSettling game:");
     console.log("  Winner: seat", winnerSeatIndex, "(admin)");
     console.log("  Final pot:", finalPot.toString());
 
+    const [settlementPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("settlement"), gamePda.toBuffer()],
+      program.programId
+    );
+    const seatAccounts = seatPdas.map((pubkey) => ({
+      pubkey,
+      isWritable: true,
+      isSigner: false,
+    }));
+
+    // Nobody folded and the board ran once in this test
+    const foldedMask = 0;
+    const secondPayouts: anchor.BN[] = [];
+
     await sendAndConfirm(
       () =>
         program.methods
-          .settleGame(winnerSeatIndex, finalPot)
+          .settleGame(totalBets, payouts, secondPayouts, foldedMask)
           .accounts({
             table: tablePda,
             game: gamePda,
-            winnerSeat: adminSeatPda,
-            winnerWallet: admin.publicKey,
-            vault: vaultPda,
+            settlement: settlementPda,
             backend: admin.publicKey,
-            systemProgram: anchor.web3.SystemProgram.programId,
           })
+          .remainingAccounts(seatAccounts)
           .rpc(),
       "settleGame"
     );
 
-    // Game PDA is closed after settlement, so we can't fetch it anymore
+    // Nothing moves until the dispute window closes
+    const settlement = await program.account.settlement.fetch(settlementPda);
+    console.log("  Challenges open until slot", settlement.deadlineSlot.toString());
+    while ((await connection.getSlot()) <= settlement.deadlineSlot.toNumber()) {
+      await sleep(2000);
+    }
+
+    await sendAndConfirm(
+      () =>
+        program.methods
+          .finalizeSettlement()
+          .accounts({
+            table: tablePda,
+            game: gamePda,
+            settlement: settlementPda,
+            backend: admin.publicKey,
+          })
+          .remainingAccounts(seatAccounts)
+          .rpc(),
+      "finalizeSettlement"
+    );
+
+    // Game and settlement PDAs are closed after finalization
+    const winnerSeat = await program.account.playerSeat.fetch(adminSeatPda);
     console.log("\nGame settled:");
     console.log("  Game PDA closed (rent reclaimed to backend)");
     console.log("  Winner: seat", winnerSeatIndex);
     console.log("  Payout:", finalPot.toString(), "lamports");
+    console.log("  Winner stack:", winnerSeat.chips.toString());
   });
 
   // ========================================